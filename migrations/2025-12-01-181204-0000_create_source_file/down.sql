@@ -0,0 +1,2 @@
+DROP TABLE source_file_kanji;
+DROP TABLE source_file;
\ No newline at end of file
@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS source_file (
+  id INTEGER NOT NULL PRIMARY KEY,
+  source_id INTEGER NOT NULL,
+  path TEXT NOT NULL,
+  size BIGINT NOT NULL,
+  modified_at BIGINT NOT NULL,
+  scanned_at TEXT NOT NULL,
+  UNIQUE (source_id, path),
+  FOREIGN KEY (source_id) REFERENCES source (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS source_file_kanji (
+  source_file_id INTEGER NOT NULL,
+  source_id INTEGER NOT NULL,
+  kanji TEXT NOT NULL,
+  seen INTEGER NOT NULL,
+  PRIMARY KEY (source_file_id, kanji),
+  FOREIGN KEY (source_file_id) REFERENCES source_file (id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (source_id) REFERENCES source (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS source_file_kanji_source_id ON source_file_kanji (source_id);
\ No newline at end of file
@@ -0,0 +1,5 @@
+DROP TRIGGER source_snippet_au;
+DROP TRIGGER source_snippet_ad;
+DROP TRIGGER source_snippet_ai;
+DROP TABLE source_snippet_fts;
+DROP TABLE source_snippet;
\ No newline at end of file
@@ -0,0 +1,38 @@
+CREATE TABLE IF NOT EXISTS source_snippet (
+  id INTEGER NOT NULL PRIMARY KEY,
+  source_file_id INTEGER NOT NULL,
+  source_id INTEGER NOT NULL,
+  line INTEGER NOT NULL,
+  content TEXT NOT NULL,
+  kanji TEXT NOT NULL,
+  FOREIGN KEY (source_file_id) REFERENCES source_file (id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (source_id) REFERENCES source (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS source_snippet_source_file_id ON source_snippet (source_file_id);
+
+-- Each row of `kanji` holds the distinct kanji of the snippet separated by spaces,
+-- so that every kanji becomes a token on its own.
+CREATE VIRTUAL TABLE IF NOT EXISTS source_snippet_fts USING fts5 (
+  kanji,
+  content = 'source_snippet',
+  content_rowid = 'id',
+  tokenize = 'unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS source_snippet_ai AFTER INSERT ON source_snippet BEGIN
+  INSERT INTO source_snippet_fts (rowid, kanji) VALUES (new.id, new.kanji);
+END;
+
+CREATE TRIGGER IF NOT EXISTS source_snippet_ad AFTER DELETE ON source_snippet BEGIN
+  INSERT INTO source_snippet_fts (source_snippet_fts, rowid, kanji) VALUES ('delete', old.id, old.kanji);
+END;
+
+CREATE TRIGGER IF NOT EXISTS source_snippet_au AFTER UPDATE ON source_snippet BEGIN
+  INSERT INTO source_snippet_fts (source_snippet_fts, rowid, kanji) VALUES ('delete', old.id, old.kanji);
+  INSERT INTO source_snippet_fts (rowid, kanji) VALUES (new.id, new.kanji);
+END;
+
+-- Files indexed before snippets existed must be read again.
+DELETE FROM source_file_kanji;
+DELETE FROM source_file;
\ No newline at end of file
@@ -0,0 +1,4 @@
+ALTER TABLE source DROP COLUMN respect_gitignore;
+ALTER TABLE source DROP COLUMN exclude;
+ALTER TABLE source DROP COLUMN include;
+ALTER TABLE source DROP COLUMN max_depth;
\ No newline at end of file
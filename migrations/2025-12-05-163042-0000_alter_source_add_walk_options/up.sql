@@ -0,0 +1,4 @@
+ALTER TABLE source ADD COLUMN max_depth INTEGER DEFAULT 1;
+ALTER TABLE source ADD COLUMN include TEXT NOT NULL DEFAULT '["*.md","*.txt"]';
+ALTER TABLE source ADD COLUMN exclude TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE source ADD COLUMN respect_gitignore BOOLEAN NOT NULL DEFAULT FALSE;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE source DROP COLUMN encoding;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE source ADD COLUMN encoding TEXT;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE source_file DROP COLUMN warning;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE source_file ADD COLUMN warning TEXT;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE source_snippet DROP COLUMN chapter;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE source_snippet ADD COLUMN chapter INTEGER;
\ No newline at end of file
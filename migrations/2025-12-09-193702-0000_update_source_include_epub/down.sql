@@ -0,0 +1 @@
+UPDATE source SET include = '["*.md","*.txt"]' WHERE include = '["*.md","*.txt","*.epub"]';
\ No newline at end of file
@@ -0,0 +1 @@
+UPDATE source SET include = '["*.md","*.txt","*.epub"]' WHERE include = '["*.md","*.txt"]';
\ No newline at end of file
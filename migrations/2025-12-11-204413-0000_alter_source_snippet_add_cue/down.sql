@@ -0,0 +1,2 @@
+ALTER TABLE source_snippet DROP COLUMN cue_end;
+ALTER TABLE source_snippet DROP COLUMN cue_start;
\ No newline at end of file
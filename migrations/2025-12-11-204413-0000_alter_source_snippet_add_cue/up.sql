@@ -0,0 +1,2 @@
+ALTER TABLE source_snippet ADD COLUMN cue_start BIGINT;
+ALTER TABLE source_snippet ADD COLUMN cue_end BIGINT;
\ No newline at end of file
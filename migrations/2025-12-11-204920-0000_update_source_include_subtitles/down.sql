@@ -0,0 +1 @@
+UPDATE source SET include = '["*.md","*.txt","*.epub"]' WHERE include = '["*.md","*.txt","*.epub","*.srt","*.ass","*.ssa","*.vtt"]';
\ No newline at end of file
@@ -0,0 +1 @@
+UPDATE source SET include = '["*.md","*.txt","*.epub","*.srt","*.ass","*.ssa","*.vtt"]' WHERE include = '["*.md","*.txt","*.epub"]';
\ No newline at end of file
@@ -0,0 +1,6 @@
+ALTER TABLE source_snippet ADD COLUMN furigana TEXT;
+
+-- Files indexed with ruby readings as part of their content must be read again.
+DELETE FROM source_file_kanji;
+DELETE FROM source_snippet;
+DELETE FROM source_file;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE source DROP COLUMN include_headings;
\ No newline at end of file
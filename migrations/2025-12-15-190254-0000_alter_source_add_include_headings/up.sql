@@ -0,0 +1,12 @@
+ALTER TABLE source ADD COLUMN include_headings BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Markdown files indexed with their syntax, code blocks and link targets must be read again.
+DELETE FROM source_file_kanji WHERE source_file_id IN (
+  SELECT id FROM source_file WHERE lower(path) LIKE '%.md'
+);
+
+DELETE FROM source_snippet WHERE source_file_id IN (
+  SELECT id FROM source_file WHERE lower(path) LIKE '%.md'
+);
+
+DELETE FROM source_file WHERE lower(path) LIKE '%.md';
\ No newline at end of file
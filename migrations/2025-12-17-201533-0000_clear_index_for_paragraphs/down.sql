@@ -0,0 +1 @@
+-- The index is rebuilt on the next scan, so there is nothing to undo.
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- Wrapped lines of text files are now joined into paragraphs, so every file must be read again.
+DELETE FROM source_file_kanji;
+DELETE FROM source_snippet;
+DELETE FROM source_file;
\ No newline at end of file
@@ -0,0 +1,10 @@
+DROP TABLE source_snippet_fts;
+
+CREATE VIRTUAL TABLE source_snippet_fts USING fts5 (
+  kanji,
+  content = 'source_snippet',
+  content_rowid = 'id',
+  tokenize = 'unicode61'
+);
+
+INSERT INTO source_snippet_fts (source_snippet_fts) VALUES ('rebuild');
\ No newline at end of file
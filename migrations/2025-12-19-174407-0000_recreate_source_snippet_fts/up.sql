@@ -0,0 +1,15 @@
+-- Kanji outside the basic blocks and iteration marks were not indexed before.
+DELETE FROM source_file_kanji;
+DELETE FROM source_snippet;
+DELETE FROM source_file;
+
+-- The tokenizer only knows the characters of Unicode 6.1, so later ideographs such as those of
+-- Extensions E to H must be treated as tokens even though it considers them unassigned.
+DROP TABLE source_snippet_fts;
+
+CREATE VIRTUAL TABLE source_snippet_fts USING fts5 (
+  kanji,
+  content = 'source_snippet',
+  content_rowid = 'id',
+  tokenize = "unicode61 categories 'L* N* Co Cn'"
+);
\ No newline at end of file
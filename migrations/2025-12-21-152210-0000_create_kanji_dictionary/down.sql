@@ -0,0 +1 @@
+DROP TABLE kanji_dictionary;
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS kanji_dictionary (
+  id TEXT NOT NULL PRIMARY KEY,
+  on_readings TEXT NOT NULL DEFAULT '[]',
+  kun_readings TEXT NOT NULL DEFAULT '[]',
+  meanings TEXT NOT NULL DEFAULT '[]',
+  grade INTEGER,
+  jlpt INTEGER,
+  stroke_count INTEGER,
+  frequency INTEGER
+)
\ No newline at end of file
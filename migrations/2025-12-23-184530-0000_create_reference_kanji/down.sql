@@ -0,0 +1 @@
+DROP TABLE reference_kanji;
\ No newline at end of file
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS reference_kanji (
+  id TEXT NOT NULL PRIMARY KEY,
+  seen BIGINT NOT NULL
+)
\ No newline at end of file
@@ -0,0 +1,2 @@
+DROP TABLE kanji_snapshot_kanji;
+DROP TABLE kanji_snapshot;
\ No newline at end of file
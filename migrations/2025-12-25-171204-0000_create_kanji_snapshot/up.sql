@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS kanji_snapshot (
+  id INTEGER NOT NULL PRIMARY KEY,
+  source_id INTEGER NOT NULL,
+  created_at TEXT NOT NULL,
+  FOREIGN KEY (source_id) REFERENCES source (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS kanji_snapshot_kanji (
+  snapshot_id INTEGER NOT NULL,
+  kanji TEXT NOT NULL,
+  seen BIGINT NOT NULL,
+  PRIMARY KEY (snapshot_id, kanji),
+  FOREIGN KEY (snapshot_id) REFERENCES kanji_snapshot (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS kanji_snapshot_source_id ON kanji_snapshot (source_id);
+CREATE INDEX IF NOT EXISTS kanji_snapshot_kanji_kanji ON kanji_snapshot_kanji (kanji);
\ No newline at end of file
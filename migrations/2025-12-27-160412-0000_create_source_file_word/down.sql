@@ -0,0 +1 @@
+DROP TABLE source_file_word;
\ No newline at end of file
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS source_file_word (
+  source_file_id INTEGER NOT NULL,
+  source_id INTEGER NOT NULL,
+  word TEXT NOT NULL,
+  seen INTEGER NOT NULL,
+  PRIMARY KEY (source_file_id, word),
+  FOREIGN KEY (source_file_id) REFERENCES source_file (id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (source_id) REFERENCES source (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS source_file_word_source_id ON source_file_word (source_id);
+
+-- Files indexed before words were extracted are read again on the next scan, which no longer
+-- finds them up to date, while their kanji and snippets are kept until then.
+UPDATE source_file SET modified_at = -1;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE source_snippet DROP COLUMN tokens;
+ALTER TABLE source_file DROP COLUMN tokenizer;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE source_file ADD COLUMN tokenizer TEXT;
+ALTER TABLE source_snippet ADD COLUMN tokens TEXT;
\ No newline at end of file
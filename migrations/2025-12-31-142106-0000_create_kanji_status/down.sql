@@ -0,0 +1 @@
+DROP TABLE kanji_status;
\ No newline at end of file
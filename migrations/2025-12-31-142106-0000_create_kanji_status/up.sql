@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS kanji_status (
+  id TEXT NOT NULL PRIMARY KEY,
+  status TEXT NOT NULL,
+  updated_at TEXT NOT NULL
+)
\ No newline at end of file
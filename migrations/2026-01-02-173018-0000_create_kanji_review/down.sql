@@ -0,0 +1 @@
+DROP TABLE kanji_review;
\ No newline at end of file
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS kanji_review (
+  id TEXT NOT NULL PRIMARY KEY,
+  ease DOUBLE NOT NULL,
+  interval DOUBLE NOT NULL,
+  repetitions INTEGER NOT NULL,
+  lapses INTEGER NOT NULL,
+  due_at TEXT NOT NULL,
+  reviewed_at TEXT NOT NULL
+)
\ No newline at end of file
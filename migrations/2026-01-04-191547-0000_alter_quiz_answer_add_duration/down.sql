@@ -0,0 +1,2 @@
+ALTER TABLE quiz_answer DROP COLUMN confidence;
+ALTER TABLE quiz_answer DROP COLUMN duration;
\ No newline at end of file
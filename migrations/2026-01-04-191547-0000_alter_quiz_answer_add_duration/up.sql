@@ -0,0 +1,2 @@
+ALTER TABLE quiz_answer ADD COLUMN duration BIGINT;
+ALTER TABLE quiz_answer ADD COLUMN confidence TEXT;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE quiz_answer DROP COLUMN session_id;
+DROP TABLE quiz_session;
\ No newline at end of file
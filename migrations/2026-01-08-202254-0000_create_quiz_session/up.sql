@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS quiz_session (
+  id INTEGER NOT NULL PRIMARY KEY,
+  kind TEXT NOT NULL,
+  questions TEXT NOT NULL,
+  started_at TEXT NOT NULL,
+  finished_at TEXT
+);
+ALTER TABLE quiz_answer ADD COLUMN session_id INTEGER;
\ No newline at end of file
@@ -1,9 +1,20 @@
-use crate::core::kanji::{self, KanjiStats};
+use crate::command::task::OnProgress;
+use crate::core::history::{self, KanjiHistoryPoint, NewKanjiEntry};
+use crate::core::kanji::{self, KanjiDetail, KanjiStats};
 use crate::core::kanji_set::KanjiSet;
-use crate::core::snippet::{self, Snippet};
-use crate::database::sql_types::{KanjiChar, SourceId};
+use crate::core::reference::{self, FrequencyDivergences};
+use crate::core::snippet::{self, Snippet, Token};
+use crate::core::task::{Task, TaskId};
+use crate::core::word::{self, WordStats};
+use crate::core::{kanji_status, tokenizer};
+use crate::database::model::kanji_status::KanjiStatusEntry;
+use crate::database::sql_types::{KanjiChar, KanjiStatus, SourceId};
 use crate::error::CResult;
+use crate::manager::ManagerExt;
+use crate::settings;
+use std::path::PathBuf;
 use tauri::AppHandle;
+use tauri::async_runtime::spawn_blocking;
 
 #[tauri::command]
 pub async fn export_set(app: AppHandle) -> CResult<()> {
@@ -11,7 +22,7 @@ pub async fn export_set(app: AppHandle) -> CResult<()> {
     .await?
     .first()
   {
-    KanjiSet::load(&app)
+    KanjiSet::load(&app, Task::new())
       .await?
       .export(&app, folder)
       .await?;
@@ -21,15 +32,98 @@ pub async fn export_set(app: AppHandle) -> CResult<()> {
 }
 
 #[tauri::command]
-pub async fn get_set(app: AppHandle) -> CResult<KanjiSet> {
-  KanjiSet::load(&app)
+pub async fn get_frequency_divergences(
+  app: AppHandle,
+  limit: Option<usize>,
+  task: Option<TaskId>,
+  on_progress: OnProgress,
+) -> CResult<FrequencyDivergences> {
+  let task = super::task::register_task(&app, task, on_progress);
+  let limit = limit.unwrap_or(settings::DEFAULT_DIVERGENCE_LIMIT);
+  reference::divergences(app, limit, task.task())
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn get_kanji_detail(app: AppHandle, kanji: KanjiChar) -> CResult<KanjiDetail> {
+  kanji::detail(&app, kanji).map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn get_kanji_history(
+  app: AppHandle,
+  kanji: Option<KanjiChar>,
+) -> CResult<Vec<KanjiHistoryPoint>> {
+  history::series(app, kanji)
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn get_kanji_statuses(app: AppHandle) -> CResult<Vec<KanjiStatusEntry>> {
+  app
+    .database()
+    .get_kanji_status_entries()
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn get_new_kanji(app: AppHandle, days: Option<u32>) -> CResult<Vec<NewKanjiEntry>> {
+  let days = days.unwrap_or(settings::DEFAULT_NEW_KANJI_DAYS);
+  history::new_kanji(app, days)
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn get_set(
+  app: AppHandle,
+  task: Option<TaskId>,
+  on_progress: OnProgress,
+) -> CResult<KanjiSet> {
+  let task = super::task::register_task(&app, task, on_progress);
+  KanjiSet::load(&app, task.task())
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn import_kanji_dictionary(app: AppHandle, path: PathBuf) -> CResult<usize> {
+  kanji::import_dictionary(app, path)
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn import_kanji_statuses(
+  app: AppHandle,
+  path: PathBuf,
+  status: Option<KanjiStatus>,
+) -> CResult<usize> {
+  let status = status.unwrap_or(KanjiStatus::Known);
+  kanji_status::import(app, path, status)
     .await
     .map_err(Into::into)
 }
 
 #[tauri::command]
-pub async fn search_kanji(app: AppHandle) -> CResult<Vec<KanjiStats>> {
-  kanji::search(app).await.map_err(Into::into)
+pub async fn import_reference_frequencies(app: AppHandle, path: PathBuf) -> CResult<usize> {
+  reference::import(app, path)
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn search_kanji(
+  app: AppHandle,
+  task: Option<TaskId>,
+  on_progress: OnProgress,
+) -> CResult<Vec<KanjiStats>> {
+  let task = super::task::register_task(&app, task, on_progress);
+  kanji::search(app, task.task())
+    .await
+    .map_err(Into::into)
 }
 
 #[tauri::command]
@@ -38,7 +132,49 @@ pub async fn search_snippets(
   kanji: KanjiChar,
   source: Option<SourceId>,
 ) -> CResult<Vec<Snippet>> {
-  snippet::search(app, kanji, source)
+  snippet::search(app, kanji.to_string(), source)
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn search_word_snippets(
+  app: AppHandle,
+  word: String,
+  source: Option<SourceId>,
+) -> CResult<Vec<Snippet>> {
+  snippet::search(app, word, source)
     .await
     .map_err(Into::into)
 }
+
+#[tauri::command]
+pub async fn search_words(
+  app: AppHandle,
+  task: Option<TaskId>,
+  on_progress: OnProgress,
+) -> CResult<Vec<WordStats>> {
+  let task = super::task::register_task(&app, task, on_progress);
+  word::search(app, task.task())
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn set_kanji_status(
+  app: AppHandle,
+  kanjis: Vec<KanjiChar>,
+  status: KanjiStatus,
+) -> CResult<usize> {
+  app
+    .database()
+    .set_kanji_statuses(&kanjis, status)
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn tokenize_text(app: AppHandle, text: String) -> CResult<Vec<Token>> {
+  spawn_blocking(move || tokenizer::load(&app).map(|tokenizer| tokenizer.tokenize(&text)))
+    .await?
+    .map_err(Into::into)
+}
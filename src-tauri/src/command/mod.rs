@@ -3,6 +3,7 @@ pub mod kanji;
 pub mod quiz;
 pub mod source;
 pub mod source_group;
+pub mod task;
 
 use crate::error::CResult;
 use crate::manager::ManagerExt;
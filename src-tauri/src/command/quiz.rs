@@ -1,9 +1,30 @@
-use crate::core::quiz::{Quiz, QuizKind, QuizSourceStats};
+use crate::command::task::OnProgress;
+use crate::core::quiz::{
+  Quiz,
+  QuizConfusions,
+  QuizKanjiStats,
+  QuizKind,
+  QuizSessionState,
+  QuizSessionSummary,
+  QuizSourceStats,
+  is_slow_answer,
+};
+use crate::core::review::{self, Grade};
+use crate::core::task::TaskId;
+use crate::database::model::kanji_review::KanjiReview;
 use crate::database::model::quiz_answer::{NewQuizAnswer, QuizAnswer};
 use crate::database::model::quiz_chunk_history::{NewQuizChunkHistoryEntry, QuizChunkHistoryEntry};
-use crate::database::sql_types::{KanjiChar, KanjiSetChunkId, QuizAnswerId, SourceId};
+use crate::database::sql_types::{
+  KanjiChar,
+  KanjiSetChunkId,
+  QuizAnswerId,
+  QuizConfidence,
+  QuizSessionId,
+  SourceId,
+};
 use crate::error::CResult;
 use crate::manager::ManagerExt;
+use crate::settings::{self, DistractorStrategy};
 use itertools::Itertools;
 use tauri::AppHandle;
 use tauri::async_runtime::spawn_blocking;
@@ -17,8 +38,16 @@ pub async fn clear_quiz_chunk_history(app: AppHandle) -> CResult<usize> {
 }
 
 #[tauri::command]
-pub async fn create_quiz(app: AppHandle, kind: QuizKind) -> CResult<Quiz> {
-  Quiz::new(app, kind)
+pub async fn create_quiz(
+  app: AppHandle,
+  kind: QuizKind,
+  strategy: Option<DistractorStrategy>,
+  task: Option<TaskId>,
+  on_progress: OnProgress,
+) -> CResult<Quiz> {
+  let task = super::task::register_task(&app, task, on_progress);
+  let strategy = strategy.unwrap_or_else(|| settings::distractor_strategy(&app));
+  Quiz::new(app, kind, strategy, task.task())
     .await
     .map_err(Into::into)
 }
@@ -29,14 +58,41 @@ pub async fn create_quiz_answer(
   question: KanjiChar,
   answer: KanjiChar,
   source: Option<SourceId>,
+  duration: Option<u64>,
+  confidence: Option<QuizConfidence>,
+  session: Option<QuizSessionId>,
 ) -> CResult<QuizAnswerId> {
-  NewQuizAnswer::builder()
+  let correct = question == answer;
+  let slow = match duration {
+    Some(duration) if correct => is_slow_answer(&app, duration)?,
+    _ => false,
+  };
+
+  // An unknown session must be rejected before the answer is stored, not after.
+  if let Some(session) = session {
+    app.database().get_quiz_session(session)?;
+  }
+
+  let id = NewQuizAnswer::builder()
     .question(question)
     .answer(answer)
     .maybe_source_id(source)
+    .maybe_duration(duration.map(|it| i64::try_from(it).unwrap_or(i64::MAX)))
+    .maybe_confidence(confidence)
+    .maybe_session_id(session)
     .build()
-    .create(&app)
-    .map_err(Into::into)
+    .create(&app)?;
+
+  // The answer is already stored, so failing here would only make the client retry it.
+  if let Err(err) = review::record(&app, question, Grade::new(correct, slow, confidence)) {
+    log::error!("failed to update the review of {question}: {err:?}");
+  }
+
+  if let Some(session) = session {
+    QuizSessionState::finish_if_complete(&app, session)?;
+  }
+
+  Ok(id)
 }
 
 #[tauri::command]
@@ -47,6 +103,37 @@ pub async fn create_quiz_chunk_history_entry(app: AppHandle, id: KanjiSetChunkId
     .map_err(Into::into)
 }
 
+#[tauri::command]
+pub async fn create_quiz_session(
+  app: AppHandle,
+  kind: QuizKind,
+  strategy: Option<DistractorStrategy>,
+  task: Option<TaskId>,
+  on_progress: OnProgress,
+) -> CResult<QuizSessionState> {
+  let task = super::task::register_task(&app, task, on_progress);
+  let strategy = strategy.unwrap_or_else(|| settings::distractor_strategy(&app));
+  QuizSessionState::start(app, kind, strategy, task.task())
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn finish_quiz_session(app: AppHandle, id: QuizSessionId) -> CResult<QuizSessionSummary> {
+  // An unknown session would otherwise only fail once the summary is loaded.
+  app.database().get_quiz_session(id)?;
+  app.database().finish_quiz_session(id)?;
+  QuizSessionSummary::load(&app, id).map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn get_kanji_reviews(app: AppHandle) -> CResult<Vec<KanjiReview>> {
+  app
+    .database()
+    .get_kanji_reviews()
+    .map_err(Into::into)
+}
+
 #[tauri::command]
 pub async fn get_quiz_answers(app: AppHandle) -> CResult<Vec<QuizAnswer>> {
   let task = spawn_blocking(move || {
@@ -67,6 +154,27 @@ pub async fn get_quiz_chunk_history_entries(app: AppHandle) -> CResult<Vec<QuizC
     .map_err(Into::into)
 }
 
+#[tauri::command]
+pub async fn get_quiz_confusions(app: AppHandle, limit: Option<usize>) -> CResult<QuizConfusions> {
+  let limit = limit.unwrap_or(settings::DEFAULT_CONFUSION_LIMIT);
+  let task = spawn_blocking(move || QuizConfusions::load(&app, limit).map_err(Into::into));
+  task.await?
+}
+
+#[tauri::command]
+pub async fn get_quiz_kanji_stats(app: AppHandle) -> CResult<Vec<QuizKanjiStats>> {
+  let task = spawn_blocking(move || QuizKanjiStats::load(&app).map_err(Into::into));
+  task.await?
+}
+
+#[tauri::command]
+pub async fn get_quiz_session_summary(
+  app: AppHandle,
+  id: QuizSessionId,
+) -> CResult<QuizSessionSummary> {
+  QuizSessionSummary::load(&app, id).map_err(Into::into)
+}
+
 #[tauri::command]
 pub async fn get_quiz_source_stats(app: AppHandle) -> CResult<Vec<QuizSourceStats>> {
   let task = spawn_blocking(move || {
@@ -86,3 +194,19 @@ pub async fn get_quiz_source_stats(app: AppHandle) -> CResult<Vec<QuizSourceStat
 
   task.await?
 }
+
+#[tauri::command]
+pub async fn remove_quiz_session(app: AppHandle, id: QuizSessionId) -> CResult<usize> {
+  app
+    .database()
+    .remove_quiz_session(id)
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn resume_quiz_session(
+  app: AppHandle,
+  id: Option<QuizSessionId>,
+) -> CResult<QuizSessionState> {
+  QuizSessionState::resume(&app, id).map_err(Into::into)
+}
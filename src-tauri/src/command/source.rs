@@ -1,7 +1,11 @@
+use crate::core::encoding;
+use crate::core::source::globset;
 use crate::database::model::source::{NewSource, Source};
-use crate::database::sql_types::{SourceId, SourceWeight, SqlPath};
+use crate::database::model::source_file::SourceFile;
+use crate::database::sql_types::{GlobList, SourceId, SourceWeight, SqlPath};
 use crate::error::{CResult, Error};
 use crate::manager::ManagerExt;
+use encoding_rs::Encoding;
 use tauri::AppHandle;
 
 #[tauri::command]
@@ -11,11 +15,14 @@ pub async fn create_source(app: AppHandle, source: SqlPath) -> CResult<SourceId>
     return Err(Error::from(format!("Invalid source: {source}")));
   };
 
-  NewSource::builder(source)
+  let id = NewSource::builder(source)
     .name(name.as_str())
     .build()
-    .create(&app)
-    .map_err(Into::into)
+    .create(&app)?;
+
+  app.watcher().refresh(&app)?;
+
+  Ok(id)
 }
 
 #[tauri::command]
@@ -26,6 +33,14 @@ pub async fn get_source(app: AppHandle, id: SourceId) -> CResult<Source> {
     .map_err(Into::into)
 }
 
+#[tauri::command]
+pub async fn get_source_warnings(app: AppHandle, id: SourceId) -> CResult<Vec<SourceFile>> {
+  app
+    .database()
+    .get_source_file_warnings(id)
+    .map_err(Into::into)
+}
+
 #[tauri::command]
 pub async fn get_sources(app: AppHandle) -> CResult<Vec<Source>> {
   app
@@ -36,32 +51,98 @@ pub async fn get_sources(app: AppHandle) -> CResult<Vec<Source>> {
 
 #[tauri::command]
 pub async fn remove_source(app: AppHandle, id: SourceId) -> CResult<usize> {
+  let rows = app.database().remove_source(id)?;
+  app.watcher().refresh(&app)?;
+  Ok(rows)
+}
+
+#[tauri::command]
+pub async fn rename_source(app: AppHandle, id: SourceId, name: String) -> CResult<()> {
   app
     .database()
-    .remove_source(id)
+    .rename_source(id, &name)
     .map_err(Into::into)
 }
 
 #[tauri::command]
-pub async fn rename_source(app: AppHandle, id: SourceId, name: String) -> CResult<()> {
+pub async fn set_source_encoding(
+  app: AppHandle,
+  id: SourceId,
+  encoding: Option<String>,
+) -> CResult<()> {
+  let encoding = encoding
+    .as_deref()
+    .map(encoding::from_label)
+    .transpose()?
+    .map(Encoding::name);
+
   app
     .database()
-    .rename_source(id, &name)
+    .set_source_encoding(id, encoding)
     .map_err(Into::into)
 }
 
 #[tauri::command]
-pub async fn set_source_weight(app: AppHandle, id: SourceId, weight: SourceWeight) -> CResult<()> {
+pub async fn set_source_globs(
+  app: AppHandle,
+  id: SourceId,
+  include: GlobList,
+  exclude: GlobList,
+) -> CResult<()> {
+  globset(&include)?;
+  globset(&exclude)?;
+
   app
     .database()
-    .set_source_weight(id, weight)
+    .set_source_globs(id, &include, &exclude)
     .map_err(Into::into)
 }
 
 #[tauri::command]
-pub async fn toggle_source(app: AppHandle, id: SourceId, enabled: bool) -> CResult<()> {
+pub async fn set_source_include_headings(
+  app: AppHandle,
+  id: SourceId,
+  include: bool,
+) -> CResult<()> {
+  app
+    .database()
+    .set_source_include_headings(id, include)
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn set_source_max_depth(app: AppHandle, id: SourceId, depth: Option<u32>) -> CResult<()> {
+  let depth = depth.map(i32::try_from).transpose()?;
+  app
+    .database()
+    .set_source_max_depth(id, depth)?;
+  app.watcher().refresh(&app)?;
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn set_source_respect_gitignore(
+  app: AppHandle,
+  id: SourceId,
+  respect: bool,
+) -> CResult<()> {
+  app
+    .database()
+    .set_source_respect_gitignore(id, respect)
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+pub async fn set_source_weight(app: AppHandle, id: SourceId, weight: SourceWeight) -> CResult<()> {
   app
     .database()
-    .toggle_source(id, enabled)
+    .set_source_weight(id, weight)
     .map_err(Into::into)
 }
+
+#[tauri::command]
+pub async fn toggle_source(app: AppHandle, id: SourceId, enabled: bool) -> CResult<()> {
+  app.database().toggle_source(id, enabled)?;
+  app.watcher().refresh(&app)?;
+  Ok(())
+}
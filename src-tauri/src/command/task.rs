@@ -0,0 +1,45 @@
+use crate::core::task::{Task, TaskHandle, TaskId, TaskProgress};
+use crate::manager::ManagerExt;
+use serde::Deserialize;
+use tauri::ipc::{Channel, CommandArg, CommandItem, InvokeError, JavaScriptChannelId};
+use tauri::{AppHandle, Runtime};
+
+/// Channel through which a command sends the progress of its task, if the caller gave one.
+///
+/// Tauri only resolves channels that are required, so this resolves an optional one the same
+/// way, against the webview that invoked the command.
+#[derive(Default)]
+pub struct OnProgress(Option<Channel<TaskProgress>>);
+
+impl<'de, R: Runtime> CommandArg<'de, R> for OnProgress {
+  fn from_command(command: CommandItem<'de, R>) -> Result<Self, InvokeError> {
+    let (name, key) = (command.name, command.key);
+    let webview = command.message.webview();
+    let id = Option::<JavaScriptChannelId>::deserialize(command)
+      .map_err(|err| tauri::Error::InvalidArgs(name, key, err))?;
+
+    Ok(Self(id.map(|id| id.channel_on(webview))))
+  }
+}
+
+#[tauri::command]
+pub async fn cancel_task(app: AppHandle, id: TaskId) -> bool {
+  app.tasks().cancel(id)
+}
+
+/// Registers a task that sends its progress through the given channel, if any.
+pub(super) fn register_task(
+  app: &AppHandle,
+  id: Option<TaskId>,
+  on_progress: OnProgress,
+) -> TaskHandle {
+  let task = if let OnProgress(Some(channel)) = on_progress {
+    Task::with_progress(move |progress| {
+      let _ = channel.send(*progress);
+    })
+  } else {
+    Task::new()
+  };
+
+  app.tasks().register(id, task)
+}
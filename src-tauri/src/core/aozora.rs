@@ -0,0 +1,232 @@
+use crate::core::kanji::{is_iteration_mark, is_kanji};
+use crate::core::snippet::Ruby;
+
+/// Marks the start of the base text of a ruby, as in `｜漢字《かんじ》`.
+pub const RUBY_START: char = '｜';
+pub const READING_START: char = '《';
+pub const READING_END: char = '》';
+
+/// Returns the lines of the text with their index, leaving out the header explaining the
+/// notation and the footer with the bibliographic information of Aozora Bunko files.
+pub fn body_lines(text: &str) -> Vec<(usize, &str)> {
+  let mut lines = text.lines().enumerate().collect::<Vec<_>>();
+  if !text.contains("［＃") && !text.contains(READING_START) {
+    return lines;
+  }
+
+  if let Some(footer) = lines
+    .iter()
+    .position(|(_, line)| line.starts_with("底本："))
+  {
+    lines.truncate(footer);
+  }
+
+  let separators = lines
+    .iter()
+    .take(50)
+    .enumerate()
+    .filter(|(_, (_, line))| is_separator(line))
+    .map(|(position, _)| position)
+    .take(2)
+    .collect::<Vec<_>>();
+
+  if let [start, end] = separators[..] {
+    lines.drain(start..=end);
+  }
+
+  lines
+}
+
+/// Removes `［＃...］` annotations and ruby readings from the line, returning the readings
+/// separately as offsets into the remaining text.
+pub fn parse_line(line: &str) -> (String, Vec<Ruby>) {
+  let chars = line.chars().collect::<Vec<_>>();
+  let mut text = Vec::with_capacity(chars.len());
+  let mut rubies = Vec::new();
+  let mut base_start = None;
+  let mut index = 0;
+
+  while let Some(&c) = chars.get(index) {
+    index += 1;
+
+    if c == RUBY_START {
+      base_start = Some(text.len());
+    } else if c == '［' && chars.get(index) == Some(&'＃') {
+      index = skip_annotation(&chars, index);
+    } else if c == READING_START
+      && let Some(len) = chars[index..]
+        .iter()
+        .position(|it| *it == READING_END)
+      && let reading = chars[index..index + len]
+        .iter()
+        .collect::<String>()
+      && is_reading(&reading)
+    {
+      index += len + 1;
+
+      let start = base_start
+        .take()
+        .unwrap_or_else(|| implicit_base_start(&text));
+
+      if start < text.len() {
+        rubies.push(Ruby {
+          start,
+          len: text.len() - start,
+          reading,
+        });
+      }
+    } else {
+      text.push(c);
+    }
+  }
+
+  (text.into_iter().collect(), rubies)
+}
+
+/// Without a `｜`, the base is the run of characters of the same kind before the reading.
+fn implicit_base_start(text: &[char]) -> usize {
+  let Some(&last) = text.last() else {
+    return 0;
+  };
+
+  let kind = CharKind::of(last);
+  text
+    .iter()
+    .rposition(|c| CharKind::of(*c) != kind)
+    .map_or(0, |position| position + 1)
+}
+
+fn skip_annotation(chars: &[char], mut index: usize) -> usize {
+  let mut depth = 1usize;
+  while let Some(&c) = chars.get(index) {
+    index += 1;
+    match c {
+      '［' => depth += 1,
+      '］' => depth -= 1,
+      _ => {}
+    }
+
+    if depth == 0 {
+      break;
+    }
+  }
+
+  index
+}
+
+fn is_reading(text: &str) -> bool {
+  !text.is_empty() && text.chars().all(is_kana)
+}
+
+fn is_kana(c: char) -> bool {
+  matches!(c, '\u{3041}'..='\u{309f}' | '\u{30a0}'..='\u{30ff}')
+}
+
+fn is_separator(line: &str) -> bool {
+  let line = line.trim();
+  line.len() >= 10 && line.chars().all(|c| c == '-')
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharKind {
+  Kanji,
+  Hiragana,
+  Katakana,
+  Latin,
+  Other,
+}
+
+impl CharKind {
+  fn of(c: char) -> Self {
+    if is_kanji(c) || is_iteration_mark(c) || c == 'ヶ' {
+      Self::Kanji
+    } else if matches!(c, '\u{3041}'..='\u{309f}') {
+      Self::Hiragana
+    } else if matches!(c, '\u{30a0}'..='\u{30ff}') {
+      Self::Katakana
+    } else if c.is_alphanumeric() {
+      Self::Latin
+    } else {
+      Self::Other
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parsed(line: &str) -> (String, Vec<(usize, usize, String)>) {
+    let (text, rubies) = parse_line(line);
+    let rubies = rubies
+      .into_iter()
+      .map(|ruby| (ruby.start, ruby.len, ruby.reading))
+      .collect();
+
+    (text, rubies)
+  }
+
+  #[test]
+  fn reads_explicit_rubies() {
+    assert_eq!(
+      parsed("｜東京《とうきょう》へ行く"),
+      (
+        "東京へ行く".to_owned(),
+        vec![(0, 2, "とうきょう".to_owned())]
+      )
+    );
+  }
+
+  #[test]
+  fn reads_implicit_rubies() {
+    assert_eq!(
+      parsed("私は漢字《かんじ》とカタカナ《かたかな》を"),
+      (
+        "私は漢字とカタカナを".to_owned(),
+        vec![(2, 2, "かんじ".to_owned()), (5, 4, "かたかな".to_owned())]
+      )
+    );
+  }
+
+  #[test]
+  fn removes_annotations() {
+    assert_eq!(
+      parsed("第一章［＃「第一章」は大見出し［＃注］］"),
+      ("第一章".to_owned(), vec![])
+    );
+  }
+
+  #[test]
+  fn keeps_brackets_without_readings() {
+    assert_eq!(parsed("《書名》"), ("《書名》".to_owned(), vec![]));
+  }
+
+  #[test]
+  fn leaves_out_header_and_footer() {
+    let text = "\
+タイトル
+作者
+
+-------------------------------------------------------
+【テキスト中に現れる記号について】
+《》：ルビ
+-------------------------------------------------------
+
+本文《ほんぶん》です。
+
+底本：「タイトル」出版社
+";
+
+    let indices = body_lines(text)
+      .into_iter()
+      .map(|(index, _)| index)
+      .collect::<Vec<_>>();
+
+    assert_eq!(indices, [0, 1, 2, 7, 8, 9]);
+  }
+
+  #[test]
+  fn keeps_plain_text() {
+    assert_eq!(body_lines("一行目\n底本：なし").len(), 2);
+  }
+}
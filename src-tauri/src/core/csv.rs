@@ -0,0 +1,47 @@
+/// Splits a line of CSV or TSV into trimmed fields, where fields may be quoted to contain
+/// the delimiter, and quotes are escaped by doubling them.
+pub fn split_fields(line: &str, delimiter: char) -> Vec<String> {
+  let mut fields = Vec::new();
+  let mut field = String::new();
+  let mut quoted = false;
+  let mut chars = line.chars().peekable();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '"' if quoted && chars.peek() == Some(&'"') => {
+        field.push('"');
+        chars.next();
+      }
+      '"' => quoted = !quoted,
+      c if c == delimiter && !quoted => {
+        fields.push(field.trim().to_owned());
+        field.clear();
+      }
+      c => field.push(c),
+    }
+  }
+
+  fields.push(field.trim().to_owned());
+  fields
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn splits_and_trims_fields() {
+    assert_eq!(split_fields("a, b ,c", ','), ["a", "b", "c"]);
+    assert_eq!(split_fields("a\t\tc", '\t'), ["a", "", "c"]);
+    assert_eq!(split_fields("", ','), [""]);
+  }
+
+  #[test]
+  fn keeps_delimiters_in_quotes() {
+    assert_eq!(split_fields("日,\"1,234\"", ','), ["日", "1,234"]);
+    assert_eq!(
+      split_fields("\"say \"\"hi\"\"\",x", ','),
+      ["say \"hi\"", "x"]
+    );
+  }
+}
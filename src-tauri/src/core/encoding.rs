@@ -0,0 +1,77 @@
+use anyhow::{Result, anyhow, bail};
+use encoding_rs::{EUC_JP, Encoding, SHIFT_JIS, UTF_8, UTF_16BE, UTF_16LE};
+
+/// Looks up an encoding by its WHATWG label, such as `shift_jis` or `euc-jp`.
+pub fn from_label(label: &str) -> Result<&'static Encoding> {
+  Encoding::for_label(label.trim().as_bytes()).ok_or_else(|| anyhow!("Unknown encoding: {label}"))
+}
+
+/// Decodes the bytes, failing instead of inserting replacement characters.
+///
+/// A byte order mark always wins. Otherwise, the given encoding is used, if any.
+/// Without one, the encoding is guessed between UTF-8, UTF-16, `Shift_JIS` and EUC-JP.
+pub fn decode(bytes: &[u8], encoding: Option<&'static Encoding>) -> Result<String> {
+  if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+    return decode_with(&bytes[bom_len..], encoding);
+  }
+
+  if let Some(encoding) = encoding {
+    return decode_with(bytes, encoding);
+  }
+
+  if let Ok(text) = str::from_utf8(bytes) {
+    return Ok(text.to_owned());
+  }
+
+  if let Some(encoding) = guess_utf16(bytes) {
+    return decode_with(bytes, encoding);
+  }
+
+  [SHIFT_JIS, EUC_JP]
+    .into_iter()
+    .filter_map(|encoding| decode_with(bytes, encoding).ok())
+    .max_by_key(|text| count_kana(text))
+    .ok_or_else(|| anyhow!("Could not detect the encoding"))
+}
+
+fn decode_with(bytes: &[u8], encoding: &'static Encoding) -> Result<String> {
+  if encoding == UTF_8 {
+    return Ok(str::from_utf8(bytes)?.to_owned());
+  }
+
+  let Some(text) = encoding.decode_without_bom_handling_and_without_replacement(bytes) else {
+    bail!("File is not valid {}", encoding.name());
+  };
+
+  Ok(text.into_owned())
+}
+
+/// UTF-16 text without a byte order mark is recognized by the zero bytes of its ASCII characters.
+fn guess_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+  if bytes.is_empty() || !bytes.len().is_multiple_of(2) {
+    return None;
+  }
+
+  let (mut even, mut odd) = (0usize, 0usize);
+  for pair in bytes.chunks_exact(2) {
+    even = even.saturating_add(usize::from(pair[0] == 0));
+    odd = odd.saturating_add(usize::from(pair[1] == 0));
+  }
+
+  let threshold = bytes.len() / 8;
+  if odd > threshold && even < odd / 4 {
+    Some(UTF_16LE)
+  } else if even > threshold && odd < even / 4 {
+    Some(UTF_16BE)
+  } else {
+    None
+  }
+}
+
+/// Kana are rare in a wrong guess, so they tell `Shift_JIS` and EUC-JP apart better than kanji.
+fn count_kana(text: &str) -> usize {
+  text
+    .chars()
+    .filter(|c| matches!(c, '\u{3041}'..='\u{309f}' | '\u{30a0}'..='\u{30ff}'))
+    .count()
+}
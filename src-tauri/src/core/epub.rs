@@ -0,0 +1,187 @@
+use crate::core::aozora::{READING_END, READING_START, RUBY_START};
+use crate::core::encoding;
+use anyhow::{Context, Result};
+use percent_encoding::percent_decode_str;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use scraper::{ElementRef, Html};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use url::Url;
+use zip::ZipArchive;
+
+/// Elements whose text is never part of the reading.
+const SKIPPED: &[&str] = &["head", "rp", "rt", "script", "style"];
+
+/// Elements that start a new paragraph.
+const BLOCKS: &[&str] = &[
+  "blockquote",
+  "br",
+  "dd",
+  "div",
+  "dt",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "li",
+  "p",
+  "section",
+  "td",
+  "th",
+];
+
+/// Reads the documents of the spine in order, returning the paragraphs of each chapter.
+pub fn read(path: &Path) -> Result<Vec<Vec<String>>> {
+  let mut archive = ZipArchive::new(File::open(path)?)?;
+  let container = read_entry(&mut archive, "META-INF/container.xml")?;
+  let opf_path = select_attr(&container, "rootfile", "full-path")?
+    .into_iter()
+    .next()
+    .context("EPUB has no package document")?;
+
+  let opf = read_entry(&mut archive, &opf_path)?;
+  let base = Url::parse("epub:/")?.join(&opf_path)?;
+  let manifest: HashMap<String, String> = select(&opf, "item")?
+    .into_iter()
+    .filter_map(|mut attrs| Some((attrs.remove("id")?, attrs.remove("href")?)))
+    .collect();
+
+  let mut chapters = Vec::new();
+  for idref in select_attr(&opf, "itemref", "idref")? {
+    let Some(href) = manifest.get(&idref) else {
+      continue;
+    };
+
+    let url = base.join(href)?;
+    let name = percent_decode_str(url.path().trim_start_matches('/')).decode_utf8()?;
+    let document = Html::parse_document(&read_entry(&mut archive, &name)?);
+
+    let mut paragraphs = Vec::new();
+    let mut current = String::new();
+    collect_text(document.root_element(), &mut paragraphs, &mut current);
+    flush(&mut paragraphs, &mut current);
+
+    chapters.push(paragraphs);
+  }
+
+  Ok(chapters)
+}
+
+fn read_entry(archive: &mut ZipArchive<File>, name: &str) -> Result<String> {
+  let mut entry = archive
+    .by_name(name)
+    .with_context(|| format!("Missing EPUB entry: {name}"))?;
+
+  let mut bytes = Vec::new();
+  entry.read_to_end(&mut bytes)?;
+  encoding::decode(&bytes, None)
+}
+
+/// Returns the attributes of every element with the given local name, keyed by their
+/// local names, so that prefixed elements such as `opf:item` are found too.
+fn select(xml: &str, tag: &str) -> Result<Vec<HashMap<String, String>>> {
+  let mut reader = Reader::from_str(xml);
+  let mut elements = Vec::new();
+
+  loop {
+    match reader.read_event()? {
+      Event::Start(start) | Event::Empty(start)
+        if start.local_name().as_ref() == tag.as_bytes() =>
+      {
+        let mut attrs = HashMap::new();
+        for attr in start.attributes() {
+          let attr = attr?;
+          let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+          let value = attr.decode_and_unescape_value(reader.decoder())?;
+          attrs.insert(key, value.into_owned());
+        }
+
+        elements.push(attrs);
+      }
+      Event::Eof => break,
+      _ => {}
+    }
+  }
+
+  Ok(elements)
+}
+
+fn select_attr(xml: &str, tag: &str, attr: &str) -> Result<Vec<String>> {
+  let values = select(xml, tag)?
+    .into_iter()
+    .filter_map(|mut attrs| attrs.remove(attr))
+    .collect();
+
+  Ok(values)
+}
+
+fn collect_text(element: ElementRef<'_>, paragraphs: &mut Vec<String>, current: &mut String) {
+  for child in element.children() {
+    if let Some(text) = child.value().as_text() {
+      current.push_str(text);
+    } else if let Some(child) = ElementRef::wrap(child) {
+      let name = child.value().name();
+      if SKIPPED.contains(&name) {
+        continue;
+      }
+
+      // Ruby is written in Aozora notation, so that readings are parsed like in text files.
+      if name == "ruby" {
+        let mut base = String::new();
+        let mut reading = String::new();
+        collect_ruby(child, &mut base, &mut reading);
+
+        if reading.is_empty() {
+          current.push_str(&base);
+        } else {
+          current.push(RUBY_START);
+          current.push_str(&base);
+          current.push(READING_START);
+          current.push_str(&reading);
+          current.push(READING_END);
+        }
+
+        continue;
+      }
+
+      let is_block = BLOCKS.contains(&name);
+      if is_block {
+        flush(paragraphs, current);
+      }
+
+      collect_text(child, paragraphs, current);
+
+      if is_block {
+        flush(paragraphs, current);
+      }
+    }
+  }
+}
+
+fn collect_ruby(element: ElementRef<'_>, base: &mut String, reading: &mut String) {
+  for child in element.children() {
+    if let Some(text) = child.value().as_text() {
+      base.push_str(text);
+    } else if let Some(child) = ElementRef::wrap(child) {
+      match child.value().name() {
+        "rt" => reading.extend(child.text()),
+        "rp" => {}
+        _ => collect_ruby(child, base, reading),
+      }
+    }
+  }
+}
+
+fn flush(paragraphs: &mut Vec<String>, current: &mut String) {
+  let paragraph = current.trim();
+  if !paragraph.is_empty() {
+    paragraphs.push(paragraph.to_owned());
+  }
+
+  current.clear();
+}
@@ -0,0 +1,174 @@
+use crate::core::scan::blocking_scan;
+use crate::core::task::Task;
+use crate::core::variant;
+use crate::database::sql_types::{KanjiChar, KanjiSnapshotId, SourceId, Zoned};
+use crate::manager::ManagerExt;
+use crate::settings;
+use anyhow::Result;
+use itertools::Itertools;
+use jiff::ToSpan;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::AppHandle;
+use tauri::async_runtime::spawn_blocking;
+
+/// Totals of the enabled sources right after one of them was scanned.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KanjiHistoryPoint {
+  at: Zoned,
+
+  /// Distinct kanji across all sources.
+  kanjis: usize,
+  seen: u64,
+  sources: Vec<KanjiHistorySource>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KanjiHistorySource {
+  id: SourceId,
+  kanjis: usize,
+  seen: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewKanjiEntry {
+  character: KanjiChar,
+  first_seen: Zoned,
+}
+
+/// Returns when each kanji was first seen in any of the sources, which is the date
+/// of their oldest snapshot that has it.
+pub fn first_seen(app: &AppHandle, sources: &[SourceId]) -> Result<HashMap<KanjiChar, Zoned>> {
+  let db = app.database();
+  let iteration_marks = settings::iteration_marks(app);
+  let normalize_variants = settings::normalize_variants(app);
+  let dates: HashMap<KanjiSnapshotId, Zoned> = db
+    .get_kanji_snapshots(sources)?
+    .into_iter()
+    .map(|snapshot| (snapshot.id, snapshot.created_at))
+    .collect();
+
+  let mut first: HashMap<KanjiChar, KanjiSnapshotId> = HashMap::new();
+  for (form, snapshot) in db.get_kanji_first_snapshots(sources)? {
+    if let Some(character) = variant::fold(form, iteration_marks, normalize_variants) {
+      first
+        .entry(character)
+        .and_modify(|it| *it = (*it).min(snapshot))
+        .or_insert(snapshot);
+    }
+  }
+
+  let first_seen = first
+    .into_iter()
+    .filter_map(|(character, snapshot)| {
+      let date = dates.get(&snapshot)?.clone();
+      Some((character, date))
+    })
+    .collect();
+
+  Ok(first_seen)
+}
+
+/// Scans the enabled sources and returns the kanji first seen in the last few days,
+/// from the newest to the oldest.
+pub async fn new_kanji(app: AppHandle, days: u32) -> Result<Vec<NewKanjiEntry>> {
+  spawn_blocking(move || {
+    let sources = app.database().get_enabled_sources()?;
+    blocking_scan(&app, &sources, &Task::new())?;
+
+    let ids = sources
+      .iter()
+      .map(|source| source.id)
+      .collect_vec();
+    let since = jiff::Zoned::now().checked_sub(i64::from(days).days())?;
+    let kanjis = first_seen(&app, &ids)?
+      .into_iter()
+      .filter(|(_, first_seen)| **first_seen >= since)
+      .map(|(character, first_seen)| NewKanjiEntry { character, first_seen })
+      .sorted_by(|a, b| {
+        (*b.first_seen)
+          .cmp(&*a.first_seen)
+          .then(a.character.cmp(&b.character))
+      })
+      .collect();
+
+    Ok(kanjis)
+  })
+  .await?
+}
+
+/// Scans the enabled sources and replays their snapshots in order, returning how many
+/// kanji they had after each one. If a kanji is given, only its forms are counted.
+pub async fn series(app: AppHandle, kanji: Option<KanjiChar>) -> Result<Vec<KanjiHistoryPoint>> {
+  spawn_blocking(move || {
+    let db = app.database();
+    let sources = db.get_enabled_sources()?;
+    blocking_scan(&app, &sources, &Task::new())?;
+
+    let ids = sources
+      .iter()
+      .map(|source| source.id)
+      .collect_vec();
+    let forms = kanji.map(|kanji| variant::search_forms(&app, kanji));
+    let iteration_marks = settings::iteration_marks(&app);
+    let normalize_variants = settings::normalize_variants(&app);
+
+    let mut counts: HashMap<KanjiSnapshotId, HashMap<KanjiChar, u64>> = HashMap::new();
+    for row in db.get_kanji_snapshot_kanjis(&ids, forms.as_deref())? {
+      if let Some(character) = variant::fold(row.kanji, iteration_marks, normalize_variants) {
+        let seen = counts
+          .entry(row.snapshot_id)
+          .or_default()
+          .entry(character)
+          .or_default();
+
+        *seen = seen.saturating_add(u64::try_from(row.seen).unwrap_or(0));
+      }
+    }
+
+    let mut latest: HashMap<SourceId, HashMap<KanjiChar, u64>> = HashMap::new();
+    let mut points = Vec::new();
+
+    for snapshot in db.get_kanji_snapshots(&ids)? {
+      let kanjis = counts
+        .remove(&snapshot.id)
+        .unwrap_or_default();
+      latest.insert(snapshot.source_id, kanjis);
+
+      let sources = latest
+        .iter()
+        .map(|(id, kanjis)| {
+          KanjiHistorySource {
+            id: *id,
+            kanjis: kanjis.len(),
+            seen: kanjis
+              .values()
+              .copied()
+              .fold(0, u64::saturating_add),
+          }
+        })
+        .sorted_by_key(|source| source.id)
+        .collect_vec();
+
+      points.push(KanjiHistoryPoint {
+        at: snapshot.created_at,
+        kanjis: latest
+          .values()
+          .flat_map(HashMap::keys)
+          .unique()
+          .count(),
+        seen: sources
+          .iter()
+          .map(|source| source.seen)
+          .fold(0, u64::saturating_add),
+        sources,
+      });
+    }
+
+    Ok(points)
+  })
+  .await?
+}
@@ -1,17 +1,25 @@
-use crate::database::sql_types::{KanjiChar, SourceId};
+use crate::database::model::kanji_dictionary::KanjiDictionaryEntry;
+use crate::database::sql_types::{KanjiChar, KanjiStatus, SourceId, Zoned};
 use serde::Serialize;
 use std::sync::Arc;
 
 #[cfg(desktop)]
 use {
-  crate::core::source::walk_source,
+  crate::core::history,
+  crate::core::kanji_status::KanjiStatuses,
+  crate::core::kanjidic,
+  crate::core::reference::{self, Reference},
+  crate::core::scan::blocking_scan,
+  crate::core::task::Task,
+  crate::core::variant,
   crate::database::model::kanji::NewKanji,
   crate::database::model::source::Source,
-  crate::database::sql_types::Zoned,
   crate::manager::ManagerExt,
+  crate::settings::{self, LevelMode, Levels},
   anyhow::Result,
+  itertools::Itertools,
   std::collections::HashMap,
-  std::fs,
+  std::path::PathBuf,
   tauri::AppHandle,
   tauri::async_runtime::spawn_blocking,
 };
@@ -22,8 +30,29 @@ pub struct KanjiStats {
   character: KanjiChar,
   seen: u32,
   ratio: f64,
-  level: Level,
+  level: Arc<str>,
+  status: KanjiStatus,
   sources: Vec<KanjiStatsSource>,
+
+  /// Raw forms of the kanji found in the sources, which only differ from
+  /// the character itself when variants are normalized.
+  forms: Vec<KanjiStatsForm>,
+
+  dictionary: Option<KanjiDictionaryEntry>,
+
+  /// Rank of the kanji in the reference frequency list, if it is in there.
+  reference_rank: Option<u32>,
+
+  /// Ratio of the kanji in the reference frequency list, if one was imported.
+  reference_ratio: Option<f64>,
+
+  /// How over-represented the kanji is in the sources compared with the reference list,
+  /// as explained in [`reference::deviation`](crate::core::reference::deviation).
+  deviation: Option<f64>,
+
+  /// Date of the oldest snapshot of the sources in which the kanji appears.
+  first_seen: Option<Zoned>,
+
   quizzes: u64,
   correct_quiz_answers: u64,
   quiz_accuracy: f64,
@@ -35,8 +64,15 @@ impl KanjiStats {
       character: kanji,
       seen: 0,
       ratio: 0.0,
-      level: Level::Unknown,
+      level: Arc::default(),
+      status: KanjiStatus::default(),
       sources: Vec::default(),
+      forms: Vec::default(),
+      dictionary: None,
+      reference_rank: None,
+      reference_ratio: None,
+      deviation: None,
+      first_seen: None,
       quizzes: 0,
       correct_quiz_answers: 0,
       quiz_accuracy: 0.0,
@@ -50,6 +86,18 @@ impl KanjiStats {
   pub fn seen(&self) -> u32 {
     self.seen
   }
+
+  pub fn ratio(&self) -> f64 {
+    self.ratio
+  }
+
+  pub fn level(&self) -> &Arc<str> {
+    &self.level
+  }
+
+  pub fn status(&self) -> KanjiStatus {
+    self.status
+  }
 }
 
 #[derive(Serialize)]
@@ -61,42 +109,91 @@ struct KanjiStatsSource {
 }
 
 #[derive(Serialize)]
-#[serde(rename_all = "kebab-case")]
-enum Level {
-  Common,
-  Uncommon,
-  Rare,
-  VeryRare,
-  Unknown,
-}
-
-impl Level {
-  const fn from_ratio(mut ratio: f64) -> Self {
-    ratio *= 100.0;
-    if ratio >= 0.1 {
-      Self::Common
-    } else if ratio >= 0.01 {
-      Self::Uncommon
-    } else if ratio >= 0.001 {
-      Self::Rare
-    } else if ratio >= 0.0001 {
-      Self::VeryRare
-    } else {
-      Self::Unknown
+#[serde(rename_all = "camelCase")]
+struct KanjiStatsForm {
+  character: KanjiChar,
+  seen: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KanjiDetail {
+  character: KanjiChar,
+  dictionary: Option<KanjiDictionaryEntry>,
+
+  /// Other forms of the kanji, according to the bundled variant table.
+  variants: Vec<KanjiChar>,
+
+  status: KanjiStatus,
+  first_seen: Option<Zoned>,
+  quizzes: u64,
+  correct_quiz_answers: u64,
+}
+
+/// Assigns levels to kanji according to the thresholds of the settings.
+#[cfg(desktop)]
+pub struct LevelScale {
+  mode: LevelMode,
+  thresholds: Vec<(Arc<str>, f64)>,
+  fallback: Arc<str>,
+}
+
+#[cfg(desktop)]
+impl LevelScale {
+  pub fn new(levels: Levels) -> Self {
+    let mut thresholds = levels
+      .thresholds
+      .into_iter()
+      .map(|threshold| (Arc::from(threshold.name), threshold.min))
+      .collect_vec();
+
+    thresholds.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    Self {
+      mode: levels.mode,
+      thresholds,
+      fallback: Arc::from(levels.fallback),
     }
   }
+
+  /// Names of the levels, from the highest to the fallback.
+  pub fn names(&self) -> impl Iterator<Item = &Arc<str>> {
+    self
+      .thresholds
+      .iter()
+      .map(|(name, _)| name)
+      .chain([&self.fallback])
+  }
+
+  /// Returns the level of a kanji given its ratio of all kanji seen and the percentile of its
+  /// frequency rank. Kanji that were never seen always get the fallback level.
+  fn level(&self, ratio: f64, percentile: f64) -> Arc<str> {
+    let value = match self.mode {
+      LevelMode::Ratio => ratio,
+      LevelMode::Percentile => percentile,
+    };
+
+    let name = self
+      .thresholds
+      .iter()
+      .find(|(_, min)| ratio > 0.0 && value >= *min)
+      .map_or(&self.fallback, |(name, _)| name);
+
+    Arc::clone(name)
+  }
 }
 
 #[cfg(desktop)]
-pub async fn search(app: AppHandle) -> Result<Vec<KanjiStats>> {
-  spawn_blocking(move || blocking_search(&app)).await?
+pub async fn search(app: AppHandle, task: Task) -> Result<Vec<KanjiStats>> {
+  spawn_blocking(move || blocking_search(&app, &task)).await?
 }
 
 #[cfg(desktop)]
-fn blocking_search(app: &AppHandle) -> Result<Vec<KanjiStats>> {
+fn blocking_search(app: &AppHandle, task: &Task) -> Result<Vec<KanjiStats>> {
   let sources = app.database().get_enabled_sources()?;
   blocking_search_with_options(app)
     .sources(&sources)
+    .task(task)
     .call()
 }
 
@@ -105,53 +202,110 @@ fn blocking_search(app: &AppHandle) -> Result<Vec<KanjiStats>> {
 pub fn blocking_search_with_options(
   #[builder(start_fn)] app: &AppHandle,
   #[builder(default)] sources: &[Source],
+  task: Option<&Task>,
 ) -> Result<Vec<KanjiStats>> {
   let db = app.database();
+  let task = task.cloned().unwrap_or_default();
+  blocking_scan(app, sources, &task)?;
+
+  let names: HashMap<SourceId, Arc<str>> = sources
+    .iter()
+    .map(|source| (source.id, Arc::from(source.name.as_str())))
+    .collect();
+
+  let ids = names.keys().copied().collect_vec();
+  let iteration_marks = settings::iteration_marks(app);
+  let normalize_variants = settings::normalize_variants(app);
+  let statuses = KanjiStatuses::load(app)?;
   let mut kanjis: HashMap<KanjiChar, KanjiStats> = HashMap::new();
 
-  for source in sources {
-    let id = source.id;
-    let name: Arc<str> = Arc::from(source.name.as_str());
-
-    for file in walk_source(source) {
-      for character in fs::read_to_string(&file)?
-        .chars()
-        .filter_map(KanjiChar::from_char)
-      {
-        let kanji = kanjis
-          .entry(character)
-          .or_insert_with(|| KanjiStats::new(character));
-
-        kanji.seen = kanji.seen.saturating_add(1);
-
-        if let Some(source) = kanji
-          .sources
-          .iter_mut()
-          .find(|it| it.name == name)
-        {
-          source.seen = source.seen.saturating_add(1);
-        } else {
-          let name = Arc::clone(&name);
-          kanji
-            .sources
-            .push(KanjiStatsSource { id, name, seen: 1 });
-        }
-      }
+  for (form, id, seen) in db.get_kanji_counts(&ids)? {
+    let Some(character) = variant::fold(form, iteration_marks, normalize_variants) else {
+      continue;
+    };
+
+    let status = statuses.get(character);
+
+    // Ignored kanji are left out entirely, so that they do not count towards the ratios.
+    if status == KanjiStatus::Ignored {
+      continue;
+    }
+
+    let seen = u32::try_from(seen).unwrap_or(u32::MAX);
+    let kanji = kanjis
+      .entry(character)
+      .or_insert_with(|| KanjiStats::new(character));
+
+    kanji.status = status;
+    kanji.seen = kanji.seen.saturating_add(seen);
+
+    if let Some(source) = kanji
+      .sources
+      .iter_mut()
+      .find(|source| source.id == id)
+    {
+      source.seen = source.seen.saturating_add(seen);
+    } else {
+      let name = Arc::clone(&names[&id]);
+      kanji
+        .sources
+        .push(KanjiStatsSource { id, name, seen });
+    }
+
+    if let Some(raw) = kanji
+      .forms
+      .iter_mut()
+      .find(|raw| raw.character == form)
+    {
+      raw.seen = raw.seen.saturating_add(seen);
+    } else {
+      kanji
+        .forms
+        .push(KanjiStatsForm { character: form, seen });
     }
   }
 
+  let mut dictionary: HashMap<KanjiChar, KanjiDictionaryEntry> = db
+    .get_kanji_dictionary()?
+    .into_iter()
+    .map(|entry| (entry.id, entry))
+    .collect();
+
   let total = kanjis
     .values()
     .map(|kanji| u64::from(kanji.seen))
     .fold(0u64, u64::saturating_add) as f64;
 
+  let reference = Reference::load(app)?;
+  let mut first_seen = history::first_seen(app, &ids)?;
+  let scale = LevelScale::new(settings::levels(app));
+  let ranks = kanjis
+    .values()
+    .map(KanjiStats::seen)
+    .sorted_unstable()
+    .collect_vec();
+
   let now = Zoned::now();
   for kanji in kanjis.values_mut() {
+    task.check()?;
+
     if total.is_normal() {
       kanji.ratio = f64::from(kanji.seen) / total;
-      kanji.level = Level::from_ratio(kanji.ratio);
     }
 
+    let below = ranks.partition_point(|seen| *seen < kanji.seen);
+    let percentile = (below as f64) / (ranks.len() as f64) * 100.0;
+    kanji.level = scale.level(kanji.ratio, percentile);
+
+    if !reference.is_empty() {
+      let reference_ratio = reference.ratio(kanji.character);
+      kanji.reference_rank = reference.rank(kanji.character);
+      kanji.reference_ratio = Some(reference_ratio);
+      kanji.deviation = Some(reference::deviation(kanji.ratio, reference_ratio));
+    }
+
+    kanji.dictionary = dictionary.remove(&kanji.character);
+    kanji.first_seen = first_seen.remove(&kanji.character);
     kanji.quizzes = db.count_quizzes(kanji.character)?;
 
     if kanji.quizzes > 0 {
@@ -169,7 +323,123 @@ pub fn blocking_search_with_options(
   Ok(kanjis.into_values().collect())
 }
 
+#[cfg(desktop)]
+pub fn detail(app: &AppHandle, kanji: KanjiChar) -> Result<KanjiDetail> {
+  let db = app.database();
+  let sources = db
+    .get_enabled_sources()?
+    .into_iter()
+    .map(|source| source.id)
+    .collect_vec();
+
+  // The first-seen dates are folded into the canonical form when normalizing variants.
+  let counted = if settings::normalize_variants(app) {
+    variant::canonical(kanji)
+  } else {
+    kanji
+  };
+  let variants = variant::forms(kanji)
+    .into_iter()
+    .filter(|form| *form != kanji)
+    .collect();
+
+  Ok(KanjiDetail {
+    character: kanji,
+    dictionary: db.get_kanji_dictionary_entry(kanji)?,
+    variants,
+    status: KanjiStatuses::load(app)?.get(kanji),
+    first_seen: history::first_seen(app, &sources)?.remove(&counted),
+    quizzes: db.count_quizzes(kanji)?,
+    correct_quiz_answers: db.count_correct_quizzes(kanji)?,
+  })
+}
+
+/// Replaces the kanji dictionary with the entries of a KANJIDIC2 file, returning how many there are.
+#[cfg(desktop)]
+pub async fn import_dictionary(app: AppHandle, path: PathBuf) -> Result<usize> {
+  spawn_blocking(move || {
+    let entries = kanjidic::read(&path)?;
+    app
+      .database()
+      .replace_kanji_dictionary(&entries)
+  })
+  .await?
+}
+
+/// Whether the character is a CJK ideograph, from any of the unified or compatibility blocks.
 pub const fn is_kanji(c: char) -> bool {
-  // http://www.rikai.com/library/kanjitables/kanji_codes.unicode.shtml
-  matches!(c, '\u{4e00}'..='\u{9faf}' | '\u{3400}' ..= '\u{4dbf}')
+  matches!(
+    c,
+    // CJK Unified Ideographs
+    '\u{4e00}'..='\u{9fff}'
+    // Extension A
+    | '\u{3400}'..='\u{4dbf}'
+    // Extensions B to F, along with I
+    | '\u{20000}'..='\u{2a6df}'
+    | '\u{2a700}'..='\u{2ee5f}'
+    // Extensions G and H
+    | '\u{30000}'..='\u{323af}'
+    // Compatibility Ideographs and their supplement
+    | '\u{f900}'..='\u{faff}'
+    | '\u{2f800}'..='\u{2fa1f}'
+  )
+}
+
+/// Whether the character is `々` or `〆`, which only count as kanji if enabled in the settings.
+pub const fn is_iteration_mark(c: char) -> bool {
+  matches!(c, '々' | '〆')
+}
+
+/// Whether the character counts as a kanji in stats, snippets and quizzes.
+pub const fn counts_as_kanji(c: char, iteration_marks: bool) -> bool {
+  is_kanji(c) || (iteration_marks && is_iteration_mark(c))
+}
+
+#[cfg(all(test, desktop))]
+mod tests {
+  use super::*;
+  use crate::settings::LevelThreshold;
+
+  fn scale(mode: LevelMode) -> LevelScale {
+    let threshold = |name: &str, min: f64| LevelThreshold { name: name.to_owned(), min };
+    LevelScale::new(Levels {
+      mode,
+      thresholds: vec![threshold("rare", 10.0), threshold("common", 50.0)],
+      fallback: "unknown".to_owned(),
+    })
+  }
+
+  #[test]
+  fn sorts_levels_from_the_highest() {
+    let scale = scale(LevelMode::Ratio);
+    let names = scale
+      .names()
+      .map(|name| &**name)
+      .collect_vec();
+
+    assert_eq!(names, ["common", "rare", "unknown"]);
+  }
+
+  #[test]
+  fn levels_by_ratio() {
+    let scale = scale(LevelMode::Ratio);
+    assert_eq!(&*scale.level(60.0, 0.0), "common");
+    assert_eq!(&*scale.level(50.0, 0.0), "common");
+    assert_eq!(&*scale.level(20.0, 90.0), "rare");
+    assert_eq!(&*scale.level(5.0, 90.0), "unknown");
+  }
+
+  #[test]
+  fn levels_by_percentile() {
+    let scale = scale(LevelMode::Percentile);
+    assert_eq!(&*scale.level(1.0, 90.0), "common");
+    assert_eq!(&*scale.level(60.0, 20.0), "rare");
+    assert_eq!(&*scale.level(60.0, 5.0), "unknown");
+  }
+
+  #[test]
+  fn never_levels_unseen_kanji() {
+    let scale = scale(LevelMode::Percentile);
+    assert_eq!(&*scale.level(0.0, 90.0), "unknown");
+  }
 }
@@ -1,13 +1,17 @@
 use crate::database::sql_types::{KanjiChar, KanjiSetChunkId};
 use serde::Serialize;
+use std::sync::Arc;
 
 #[cfg(desktop)]
 use {
-  crate::core::kanji::{KanjiStats, search as search_kanji},
+  crate::core::kanji::{KanjiStats, LevelScale, search as search_kanji},
+  crate::core::quiz::QuizKanjiStats,
+  crate::core::task::Task,
   crate::manager::ManagerExt,
   crate::settings,
   anyhow::Result,
   itertools::Itertools,
+  std::collections::HashMap,
   std::path::Path as StdPath,
   tauri::AppHandle,
   tokio::fs::File,
@@ -25,26 +29,46 @@ pub struct KanjiSet {
 
 #[cfg(desktop)]
 impl KanjiSet {
-  pub async fn load(app: &AppHandle) -> Result<Self> {
-    let mut kanjis = search_kanji(app.clone()).await?;
+  pub async fn load(app: &AppHandle, task: Task) -> Result<Self> {
+    let mut kanjis = search_kanji(app.clone(), task).await?;
+    kanjis.retain(|kanji| !kanji.status().is_skipped());
     kanjis.sort_by_key(KanjiStats::character);
     kanjis.sort_by_key(KanjiStats::seen);
 
+    let scale = LevelScale::new(settings::levels(app));
     let mut chunks = Vec::new();
     let iter = kanjis
       .iter()
-      .map(KanjiStats::character)
       .rev()
       .chunks(settings::set_chunk_size(app));
 
+    let quiz_stats: HashMap<KanjiChar, QuizKanjiStats> = QuizKanjiStats::load(app)?
+      .into_iter()
+      .map(|stats| (stats.kanji(), stats))
+      .collect();
+
     let db = app.database();
     for (id, chunk) in (1u32..).zip(&iter) {
       let id = unsafe { KanjiSetChunkId::new_unchecked(id) };
-      let kanjis = chunk
-        .into_iter()
+      let stats = chunk.collect_vec();
+      let kanjis = stats
+        .iter()
+        .map(|kanji| kanji.character())
         .collect_vec()
         .into_boxed_slice();
 
+      let levels = scale
+        .names()
+        .filter_map(|name| {
+          let count = stats
+            .iter()
+            .filter(|kanji| kanji.level() == name)
+            .count();
+
+          (count > 0).then(|| KanjiSetLevel { name: Arc::clone(name), count })
+        })
+        .collect();
+
       let quizzes = db.count_quizzes_in(&kanjis)?;
       let mut correct_quiz_answers = 0;
       let mut quiz_accuracy = 0.0;
@@ -54,12 +78,37 @@ impl KanjiSet {
         quiz_accuracy = (correct_quiz_answers as f64) / (quizzes as f64);
       }
 
+      let chunk_stats = kanjis
+        .iter()
+        .filter_map(|kanji| quiz_stats.get(kanji))
+        .collect_vec();
+
+      let total_duration = chunk_stats.iter().fold(0i64, |acc, stats| {
+        acc.saturating_add(stats.total_duration())
+      });
+
+      let timed_answers = chunk_stats
+        .iter()
+        .fold(0u64, |acc, stats| acc.saturating_add(stats.timed_answers()));
+
+      let average_duration =
+        (timed_answers > 0).then(|| (total_duration as f64) / (timed_answers as f64));
+
+      let slow = chunk_stats
+        .iter()
+        .filter(|stats| stats.is_slow())
+        .map(|stats| stats.kanji())
+        .collect();
+
       chunks.push(KanjiSetChunk {
         id,
         kanjis,
+        levels,
         quizzes,
         correct_quiz_answers,
         quiz_accuracy,
+        average_duration,
+        slow,
       });
     }
 
@@ -83,7 +132,7 @@ impl KanjiSet {
   }
 
   pub async fn export(self, app: &AppHandle, folder: &StdPath) -> Result<()> {
-    let sets = KanjiSet::load(app).await?;
+    let sets = KanjiSet::load(app, Task::new()).await?;
     let path = folder.join(settings::set_file_name(app));
     let mut file = File::create(path).await?;
 
@@ -105,7 +154,24 @@ impl KanjiSet {
 pub struct KanjiSetChunk {
   id: KanjiSetChunkId,
   kanjis: Box<[KanjiChar]>,
+
+  /// How many kanji of the chunk belong to each level.
+  levels: Vec<KanjiSetLevel>,
+
   quizzes: u64,
   correct_quiz_answers: u64,
   quiz_accuracy: f64,
+
+  /// Average time taken by the timed correct answers, in milliseconds.
+  average_duration: Option<f64>,
+
+  /// Kanji of the chunk that are mostly answered correctly, but slowly.
+  slow: Box<[KanjiChar]>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KanjiSetLevel {
+  name: Arc<str>,
+  count: usize,
 }
@@ -0,0 +1,108 @@
+use crate::core::{encoding, variant};
+use crate::database::sql_types::{KanjiChar, KanjiStatus};
+use crate::manager::ManagerExt;
+use crate::settings;
+use anyhow::{Result, bail};
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use tauri::async_runtime::spawn_blocking;
+
+/// Statuses of the kanji, which are shared by all the forms of a kanji when variants
+/// are normalized, so that a status given to `國` applies to `国` too.
+pub struct KanjiStatuses {
+  statuses: HashMap<KanjiChar, KanjiStatus>,
+  normalize_variants: bool,
+}
+
+impl KanjiStatuses {
+  pub fn load(app: &AppHandle) -> Result<Self> {
+    Ok(Self {
+      statuses: app.database().get_kanji_statuses()?,
+      normalize_variants: settings::normalize_variants(app),
+    })
+  }
+
+  /// Returns the status of the kanji, or of the first of its forms that has one, starting with
+  /// the canonical form, when variants are normalized.
+  pub fn get(&self, kanji: KanjiChar) -> KanjiStatus {
+    let forms = if self.normalize_variants { variant::forms(kanji) } else { vec![kanji] };
+    forms
+      .iter()
+      .find_map(|form| self.statuses.get(form))
+      .copied()
+      .unwrap_or_default()
+  }
+
+  pub fn is_skipped(&self, kanji: KanjiChar) -> bool {
+    self.get(kanji).is_skipped()
+  }
+}
+
+/// Gives the status to every kanji of a file, returning how many there are.
+///
+/// The file may be a plain list of kanji, in which case all of them are taken, or the notes
+/// of an Anki deck exported as text, in which case only the first field with kanji is.
+pub async fn import(app: AppHandle, path: PathBuf, status: KanjiStatus) -> Result<usize> {
+  spawn_blocking(move || {
+    let text = encoding::decode(&fs::read(&path)?, None)?;
+    let kanjis = parse(&text);
+    if kanjis.is_empty() {
+      bail!("No kanji were found in {}", path.display());
+    }
+
+    app
+      .database()
+      .set_kanji_statuses(&kanjis, status)
+  })
+  .await?
+}
+
+fn parse(text: &str) -> Vec<KanjiChar> {
+  let mut separator = None;
+  let mut kanjis = Vec::new();
+
+  for line in text.lines() {
+    // Anki starts its exports with headers such as `#separator:tab`.
+    if let Some(header) = line.strip_prefix('#') {
+      if let Some(name) = header.strip_prefix("separator:") {
+        separator = match name.trim() {
+          "tab" => Some('\t'),
+          "comma" => Some(','),
+          "semicolon" => Some(';'),
+          "pipe" => Some('|'),
+          "space" => Some(' '),
+          "colon" => Some(':'),
+          _ => None,
+        };
+      }
+
+      continue;
+    }
+
+    let separator = separator.or_else(|| line.contains('\t').then_some('\t'));
+    let field = match separator {
+      Some(separator) => {
+        line
+          .split(separator)
+          .find(|field| {
+            field
+              .chars()
+              .any(|c| KanjiChar::from_char(c).is_some())
+          })
+          .unwrap_or_default()
+      }
+      None => line,
+    };
+
+    kanjis.extend(
+      field
+        .chars()
+        .filter_map(KanjiChar::from_char),
+    );
+  }
+
+  kanjis.into_iter().unique().collect()
+}
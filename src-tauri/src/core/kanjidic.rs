@@ -0,0 +1,157 @@
+use crate::database::model::kanji_dictionary::KanjiDictionaryEntry;
+use crate::database::sql_types::KanjiChar;
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use quick_xml::Reader;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Reads the entries of a KANJIDIC2 file, which may be compressed with gzip.
+pub fn read(path: &Path) -> Result<Vec<KanjiDictionaryEntry>> {
+  let file = File::open(path)?;
+  if path
+    .extension()
+    .is_some_and(|extension| extension.eq_ignore_ascii_case(OsStr::new("gz")))
+  {
+    parse(BufReader::new(GzDecoder::new(file)))
+  } else {
+    parse(BufReader::new(file))
+  }
+}
+
+fn parse(reader: impl BufRead) -> Result<Vec<KanjiDictionaryEntry>> {
+  let mut reader = Reader::from_reader(reader);
+  let mut buf = Vec::new();
+  let mut entries = Vec::new();
+  let mut entry = Entry::default();
+  let mut field = None;
+  let mut text = String::new();
+
+  loop {
+    match reader.read_event_into(&mut buf)? {
+      Event::Start(start) => {
+        text.clear();
+        field = Field::of(&start, &reader)?;
+      }
+      Event::Text(chunk) => text.push_str(&chunk.xml_content()?),
+      Event::CData(chunk) => text.push_str(&chunk.xml_content()?),
+      Event::GeneralRef(reference) => {
+        if let Some(c) = reference.resolve_char_ref()? {
+          text.push(c);
+        } else if let Some(value) = resolve_predefined_entity(&reference.decode()?) {
+          text.push_str(value);
+        }
+      }
+      Event::End(end) => {
+        if end.local_name().as_ref() == b"character" {
+          entries.extend(std::mem::take(&mut entry).build());
+        } else if let Some(field) = field.take() {
+          entry.set(field, text.trim());
+        }
+
+        text.clear();
+      }
+      Event::Eof => break,
+      _ => {}
+    }
+
+    buf.clear();
+  }
+
+  Ok(entries)
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+  Literal,
+  OnReading,
+  KunReading,
+  Meaning,
+  Grade,
+  Jlpt,
+  StrokeCount,
+  Frequency,
+}
+
+impl Field {
+  fn of<R>(start: &BytesStart<'_>, reader: &Reader<R>) -> Result<Option<Self>> {
+    let attribute = |name: &str| -> Result<Option<String>> {
+      let value = start
+        .try_get_attribute(name)?
+        .map(|attr| attr.decode_and_unescape_value(reader.decoder()))
+        .transpose()?
+        .map(Into::into);
+
+      Ok(value)
+    };
+
+    let field = match start.local_name().as_ref() {
+      b"literal" => Some(Self::Literal),
+      b"reading" => {
+        match attribute("r_type")?.as_deref() {
+          Some("ja_on") => Some(Self::OnReading),
+          Some("ja_kun") => Some(Self::KunReading),
+          _ => None,
+        }
+      }
+      // Meanings in other languages have an `m_lang` attribute.
+      b"meaning" if attribute("m_lang")?.is_none() => Some(Self::Meaning),
+      b"grade" => Some(Self::Grade),
+      b"jlpt" => Some(Self::Jlpt),
+      b"stroke_count" => Some(Self::StrokeCount),
+      b"freq" => Some(Self::Frequency),
+      _ => None,
+    };
+
+    Ok(field)
+  }
+}
+
+#[derive(Default)]
+struct Entry {
+  literal: Option<KanjiChar>,
+  on_readings: Vec<String>,
+  kun_readings: Vec<String>,
+  meanings: Vec<String>,
+  grade: Option<i32>,
+  jlpt: Option<i32>,
+  stroke_count: Option<i32>,
+  frequency: Option<i32>,
+}
+
+impl Entry {
+  fn set(&mut self, field: Field, value: &str) {
+    match field {
+      Field::Literal => self.literal = KanjiChar::try_from(value).ok(),
+      Field::OnReading => self.on_readings.push(value.to_owned()),
+      Field::KunReading => self.kun_readings.push(value.to_owned()),
+      Field::Meaning => self.meanings.push(value.to_owned()),
+      Field::Grade => self.grade = value.parse().ok(),
+      Field::Jlpt => self.jlpt = value.parse().ok(),
+      // The first stroke count is the accepted one, while the others are common miscounts.
+      Field::StrokeCount => {
+        self.stroke_count = self
+          .stroke_count
+          .or_else(|| value.parse().ok());
+      }
+      Field::Frequency => self.frequency = value.parse().ok(),
+    }
+  }
+
+  fn build(self) -> Option<KanjiDictionaryEntry> {
+    Some(KanjiDictionaryEntry {
+      id: self.literal?,
+      on_readings: self.on_readings.into(),
+      kun_readings: self.kun_readings.into(),
+      meanings: self.meanings.into(),
+      grade: self.grade,
+      jlpt: self.jlpt,
+      stroke_count: self.stroke_count,
+      frequency: self.frequency,
+    })
+  }
+}
@@ -0,0 +1,277 @@
+use crate::core::sentence;
+
+/// Returns the prose of a Markdown document along with the index of the line it came from.
+///
+/// Front matter, fenced code blocks, HTML comments, link targets and URLs are left out, so only
+/// paragraphs, list items, blockquotes and table rows remain. Headings are kept if asked to.
+/// Consecutive lines of a paragraph are joined, using the index of its first line.
+pub fn body_lines(text: &str, include_headings: bool) -> Vec<(usize, String)> {
+  let mut lines: Vec<(usize, String)> = Vec::new();
+  let mut block = Block::Prose;
+  let mut in_comment = false;
+  let mut open_paragraph: Option<usize> = None;
+
+  for (index, line) in text.lines().enumerate() {
+    let trimmed = line.trim();
+
+    if index == 0 && matches!(trimmed, "---" | "+++") {
+      block = Block::FrontMatter(if trimmed == "---" { "---" } else { "+++" });
+      continue;
+    }
+
+    match block {
+      Block::FrontMatter(fence) => {
+        if trimmed == fence || (fence == "---" && trimmed == "...") {
+          block = Block::Prose;
+        }
+
+        continue;
+      }
+      Block::Fenced(fence, len) => {
+        if fence_of(trimmed).is_some_and(|(it, n)| it == fence && n >= len)
+          && trimmed.trim_start_matches(fence).is_empty()
+        {
+          block = Block::Prose;
+        }
+
+        continue;
+      }
+      Block::Prose => {}
+    }
+
+    if let Some((fence, len)) = fence_of(trimmed) {
+      block = Block::Fenced(fence, len);
+      continue;
+    }
+
+    let line = strip_comments(trimmed, &mut in_comment);
+    let line = line.trim();
+
+    let continues = open_paragraph.take() == Some(index);
+    if line.is_empty() || is_reference_definition(line) || is_table_separator(line) {
+      continue;
+    }
+
+    if is_setext_underline(line) {
+      if !include_headings && continues {
+        lines.pop();
+      }
+
+      continue;
+    }
+
+    let is_table_row = line.contains('|');
+    let stripped = strip_container_markers(line);
+    let starts_block = stripped.len() != line.len() || is_table_row;
+    let (line, is_heading) = match strip_heading(stripped) {
+      Some(_) if !include_headings => continue,
+      Some(heading) => (heading, true),
+      None => (stripped, false),
+    };
+
+    let line = strip_inline(&line.replace('|', " "));
+    let line = line.trim();
+    if line.is_empty() {
+      continue;
+    }
+
+    match lines.last_mut() {
+      Some((_, paragraph)) if continues && !starts_block && !is_heading => {
+        sentence::join(paragraph, line);
+      }
+      _ => lines.push((index, line.to_owned())),
+    }
+
+    if !is_heading && !is_table_row {
+      open_paragraph = Some(index.saturating_add(1));
+    }
+  }
+
+  lines
+}
+
+#[derive(Clone, Copy)]
+enum Block {
+  Prose,
+  FrontMatter(&'static str),
+  Fenced(char, usize),
+}
+
+fn fence_of(line: &str) -> Option<(char, usize)> {
+  let fence = line
+    .chars()
+    .next()
+    .filter(|c| matches!(c, '`' | '~'))?;
+  let len = line
+    .chars()
+    .take_while(|c| *c == fence)
+    .count();
+  (len >= 3).then_some((fence, len))
+}
+
+fn is_reference_definition(line: &str) -> bool {
+  line.starts_with('[') && line.contains("]:")
+}
+
+fn is_table_separator(line: &str) -> bool {
+  line.contains('-')
+    && line
+      .chars()
+      .all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+/// Also matches thematic breaks, which never hold any text either.
+fn is_setext_underline(line: &str) -> bool {
+  let line = line.replace(' ', "");
+  line.len() >= 3
+    && (line.chars().all(|c| c == '=')
+      || line.chars().all(|c| c == '-')
+      || line.chars().all(|c| c == '*')
+      || line.chars().all(|c| c == '_'))
+}
+
+fn strip_heading(line: &str) -> Option<&str> {
+  let hashes = line
+    .chars()
+    .take_while(|c| *c == '#')
+    .count();
+  if !(1..=6).contains(&hashes) {
+    return None;
+  }
+
+  let rest = &line[hashes..];
+  if !rest.is_empty() && !rest.starts_with(' ') {
+    return None;
+  }
+
+  Some(rest.trim().trim_end_matches('#').trim())
+}
+
+/// Removes the markers of blockquotes and list items, including task list checkboxes.
+fn strip_container_markers(mut line: &str) -> &str {
+  loop {
+    let before = line;
+    line = line.trim_start();
+
+    if let Some(rest) = line.strip_prefix('>') {
+      line = rest;
+    } else if let Some(rest) = ["- ", "* ", "+ "]
+      .iter()
+      .find_map(|marker| line.strip_prefix(marker))
+    {
+      line = rest;
+    } else if let Some(position) = line.find(['.', ')'])
+      && position > 0
+      && position <= 9
+      && line[..position]
+        .chars()
+        .all(|c| c.is_ascii_digit())
+      && line[position + 1..].starts_with(' ')
+    {
+      line = &line[position + 1..];
+    } else if let Some(rest) = ["[ ] ", "[x] ", "[X] "]
+      .iter()
+      .find_map(|marker| line.strip_prefix(marker))
+    {
+      line = rest;
+    }
+
+    if line == before {
+      return line;
+    }
+  }
+}
+
+/// Removes `<!-- -->` comments, which may span several lines.
+fn strip_comments(line: &str, in_comment: &mut bool) -> String {
+  let mut text = String::with_capacity(line.len());
+  let mut rest = line;
+
+  loop {
+    if *in_comment {
+      let Some(end) = rest.find("-->") else {
+        return text;
+      };
+
+      rest = &rest[end + 3..];
+      *in_comment = false;
+    }
+
+    let Some(start) = rest.find("<!--") else {
+      text.push_str(rest);
+      return text;
+    };
+
+    text.push_str(&rest[..start]);
+    rest = &rest[start + 4..];
+    *in_comment = true;
+  }
+}
+
+/// Keeps the text of links while removing their targets, images, inline code, HTML tags and URLs.
+fn strip_inline(line: &str) -> String {
+  let mut text = String::with_capacity(line.len());
+  let mut rest = line;
+
+  while let Some(c) = rest.chars().next() {
+    let len = c.len_utf8();
+    match c {
+      '`' => {
+        let ticks = rest
+          .chars()
+          .take_while(|it| *it == '`')
+          .count();
+        let fence = &rest[..ticks];
+        rest = rest[ticks..]
+          .find(fence)
+          .map_or("", |end| &rest[ticks + end + ticks..]);
+      }
+      '!' if rest[len..].starts_with('[') => {
+        let (_, after) = split_link(&rest[len..]);
+        rest = after;
+      }
+      '[' => {
+        let (label, after) = split_link(rest);
+        text.push_str(label);
+        rest = after;
+      }
+      '<' => {
+        rest = rest
+          .find('>')
+          .map_or("", |end| &rest[end + 1..]);
+      }
+      _ if rest.starts_with("http://") || rest.starts_with("https://") => {
+        rest = rest
+          .find(char::is_whitespace)
+          .map_or("", |end| &rest[end..]);
+      }
+      _ => {
+        text.push(c);
+        rest = &rest[len..];
+      }
+    }
+  }
+
+  text
+}
+
+/// Splits `[label](target)` or `[label][reference]` into its label and what comes after it.
+fn split_link(line: &str) -> (&str, &str) {
+  let Some(end) = line.find(']') else {
+    return (&line[1..], "");
+  };
+
+  let label = &line[1..end];
+  let after = &line[end + 1..];
+  let closing = match after.chars().next() {
+    Some('(') => ')',
+    Some('[') => ']',
+    _ => return (label, after),
+  };
+
+  let after = after
+    .find(closing)
+    .map_or("", |position| &after[position + 1..]);
+
+  (label, after)
+}
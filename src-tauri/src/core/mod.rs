@@ -3,5 +3,39 @@ pub mod kanji_set;
 pub mod quiz;
 pub mod snippet;
 
+#[cfg(desktop)]
+pub mod aozora;
+#[cfg(desktop)]
+pub mod csv;
+#[cfg(desktop)]
+pub mod encoding;
+#[cfg(desktop)]
+pub mod epub;
+#[cfg(desktop)]
+pub mod history;
+#[cfg(desktop)]
+pub mod kanji_status;
+#[cfg(desktop)]
+pub mod kanjidic;
+#[cfg(desktop)]
+pub mod markdown;
+#[cfg(desktop)]
+pub mod reference;
+#[cfg(desktop)]
+pub mod review;
+#[cfg(desktop)]
+pub mod scan;
+#[cfg(desktop)]
+pub mod sentence;
 #[cfg(desktop)]
 pub mod source;
+#[cfg(desktop)]
+pub mod subtitle;
+#[cfg(desktop)]
+pub mod task;
+#[cfg(desktop)]
+pub mod tokenizer;
+#[cfg(desktop)]
+pub mod variant;
+#[cfg(desktop)]
+pub mod word;
@@ -1,13 +1,19 @@
-use crate::core::kanji::is_kanji;
+use crate::core::kanji::counts_as_kanji;
+use crate::core::kanji_status::KanjiStatuses;
+use crate::core::quiz::distractor::Distractors;
 use crate::core::quiz::{MARUMARU, Quiz, QuizQuestion};
-use crate::core::snippet::{Snippet, blocking_search_with_options as search_snippet};
+use crate::core::scan::blocking_scan;
+use crate::core::snippet::blocking_search_with_options as search_snippet;
+use crate::core::task::Task;
+use crate::core::variant;
 use crate::database::model::source::Source;
-use crate::database::sql_types::KanjiChar;
+use crate::database::sql_types::{KanjiChar, KanjiStatus};
 use crate::manager::ManagerExt;
+use crate::settings::{self, DistractorStrategy};
 use anyhow::Result;
 use futures::future::BoxFuture;
 use itertools::Itertools;
-use rand::seq::{IteratorRandom, SliceRandom};
+use rand::seq::SliceRandom;
 use std::sync::Arc;
 use tauri::AppHandle;
 use tauri::async_runtime::spawn_blocking;
@@ -18,23 +24,57 @@ pub(super) async fn with_sources(
   app: AppHandle,
   kanjis: Vec<KanjiChar>,
   sources: Vec<Source>,
+  strategy: DistractorStrategy,
+  task: Task,
 ) -> Result<Quiz> {
-  let db = app.database();
-  let chars = Arc::from(db.get_kanji_chars()?);
-  let sources = Arc::from(sources);
+  let sources = spawn_blocking({
+    let app = app.clone();
+    let task = task.clone();
+    move || blocking_scan(&app, &sources, &task).map(|()| sources)
+  });
+
+  let sources: Arc<[Source]> = Arc::from(sources.await??);
+  let iteration_marks = settings::iteration_marks(&app);
+  let statuses = KanjiStatuses::load(&app)?;
+
+  // Known kanji may still be picked as wrong options, but ignored ones never are.
+  let pool = app
+    .database()
+    .get_kanji_chars()?
+    .into_iter()
+    .filter(|kanji| counts_as_kanji(**kanji, iteration_marks))
+    .filter(|kanji| statuses.get(*kanji) != KanjiStatus::Ignored)
+    .collect_vec();
+
+  let ids = sources
+    .iter()
+    .map(|source| source.id)
+    .collect_vec();
+
+  let distractors = Arc::new(Distractors::load(&app, strategy, pool, &ids)?);
 
   let mut set: JoinSet<Result<Option<QuizQuestion>>> = kanjis
     .into_iter()
-    .filter(|kanji| is_kanji(**kanji))
+    .filter(|kanji| counts_as_kanji(**kanji, iteration_marks))
+    .filter(|kanji| !statuses.is_skipped(*kanji))
     .unique()
-    .map(make_questions(app, chars, sources))
+    .map(make_questions(app, distractors, sources, task.clone()))
     .collect();
 
+  task.update(|progress| {
+    progress.questions_built = 0;
+    progress.questions_total = set.len() as u64;
+  });
+
   let mut questions = Vec::with_capacity(set.len());
   while let Some(question) = set.join_next().await {
     if let Some(question) = question?? {
       questions.push(question);
     }
+
+    task.update(|progress| {
+      progress.questions_built = progress.questions_built.saturating_add(1);
+    });
   }
 
   questions.shuffle(&mut rand::rng());
@@ -44,30 +84,41 @@ pub(super) async fn with_sources(
 
 fn make_questions(
   app: AppHandle,
-  chars: Arc<[KanjiChar]>,
+  distractors: Arc<Distractors>,
   sources: Arc<[Source]>,
+  task: Task,
 ) -> impl Fn(KanjiChar) -> BoxFuture<'static, Result<Option<QuizQuestion>>> {
   let semaphore = Arc::new(Semaphore::new(100));
   move |kanji| {
     let app = app.clone();
-    let chars = Arc::clone(&chars);
+    let distractors = Arc::clone(&distractors);
     let sources = Arc::clone(&sources);
     let semaphore = Arc::clone(&semaphore);
+    let task = task.clone();
 
     Box::pin(async move {
       let permit = semaphore.acquire().await?;
+      task.check()?;
+
+      let forms = variant::search_forms(&app, kanji);
       let snippet = spawn_blocking(move || {
-        search_snippet(&app, kanji)
+        search_snippet(&app, &kanji.to_string())
           .sources(&sources)
           .limit(1)
+          .max_len(settings::snippet_max_len(&app))
+          // The context could give the answer away.
+          .context(0)
           .shuffle(true)
           .call()
       });
 
       let mut question = None;
-      if let Some(snippet) = snippet.await??.pop() {
-        let censored = snippet.content().replace(*kanji, MARUMARU);
-        let options = pick_options(&snippet, kanji, &chars);
+      if let Some(mut snippet) = snippet.await??.pop() {
+        snippet.hide_readings_of(&forms);
+        let censored = snippet
+          .content()
+          .replace(|c| forms.iter().any(|form| **form == c), MARUMARU);
+        let options = distractors.pick(&snippet, kanji);
         question = Some(QuizQuestion {
           snippet,
           censored,
@@ -82,16 +133,3 @@ fn make_questions(
     })
   }
 }
-
-fn pick_options(snippet: &Snippet, answer: KanjiChar, pool: &[KanjiChar]) -> Vec<KanjiChar> {
-  let mut rng = &mut rand::rng();
-  let mut options = pool
-    .iter()
-    .copied()
-    .filter(|kanji| !snippet.contains(*kanji))
-    .sample(&mut rng, 9);
-
-  options.push(answer);
-  options.shuffle(&mut rng);
-  options
-}
@@ -0,0 +1,184 @@
+use crate::database::sql_types::{KanjiChar, Zoned};
+use serde::Serialize;
+
+#[cfg(desktop)]
+use {
+  crate::manager::ManagerExt,
+  anyhow::Result,
+  itertools::Itertools,
+  jiff::ToSpan,
+  jiff::civil::Date,
+  std::collections::{BTreeMap, HashMap},
+  tauri::AppHandle,
+};
+
+/// Wrong answers of the quizzes, telling which kanji are mistaken for which.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuizConfusions {
+  /// Most frequent pairs of a question and the wrong answer given to it.
+  pairs: Vec<ConfusionPair>,
+
+  /// Kanji answered wrongly at least once, from the most often.
+  kanjis: Vec<KanjiConfusions>,
+
+  /// Answers and mistakes of each week, from the oldest.
+  trend: Vec<ConfusionTrendPoint>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfusionPair {
+  question: KanjiChar,
+  answer: KanjiChar,
+  count: u64,
+  last_at: Zoned,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KanjiConfusions {
+  kanji: KanjiChar,
+  quizzes: u64,
+  mistakes: u64,
+
+  /// Kanji that were chosen instead, from the most often.
+  confused_with: Vec<ConfusedKanji>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfusedKanji {
+  kanji: KanjiChar,
+  count: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfusionTrendPoint {
+  /// Monday of the week.
+  week: String,
+  answers: u64,
+  mistakes: u64,
+  error_rate: f64,
+}
+
+#[cfg(desktop)]
+impl QuizConfusions {
+  /// Analyzes all the quiz answers, keeping only the most frequent pairs.
+  pub fn load(app: &AppHandle, limit: usize) -> Result<Self> {
+    let answers = app.database().get_quiz_answers()?;
+    let mut pairs: HashMap<(KanjiChar, KanjiChar), ConfusionPair> = HashMap::new();
+    let mut quizzes: HashMap<KanjiChar, u64> = HashMap::new();
+    let mut weeks: BTreeMap<Date, (u64, u64)> = BTreeMap::new();
+
+    for answer in &answers {
+      *quizzes.entry(answer.question).or_default() += 1;
+
+      let correct = answer.question == answer.answer;
+      let week = weeks
+        .entry(week_of(&answer.created_at)?)
+        .or_default();
+
+      week.0 += 1;
+      if correct {
+        continue;
+      }
+
+      week.1 += 1;
+      let pair = pairs
+        .entry((answer.question, answer.answer))
+        .or_insert_with(|| {
+          ConfusionPair {
+            question: answer.question,
+            answer: answer.answer,
+            count: 0,
+            last_at: answer.created_at.clone(),
+          }
+        });
+
+      pair.count += 1;
+      if *answer.created_at > *pair.last_at {
+        pair.last_at = answer.created_at.clone();
+      }
+    }
+
+    let pairs = pairs
+      .into_values()
+      .sorted_unstable_by(|a, b| {
+        b.count
+          .cmp(&a.count)
+          .then_with(|| (*b.last_at).cmp(&*a.last_at))
+      })
+      .collect_vec();
+
+    let kanjis = pairs
+      .iter()
+      .into_group_map_by(|pair| pair.question)
+      .into_iter()
+      .map(|(kanji, pairs)| {
+        let confused_with = pairs
+          .iter()
+          .map(|pair| {
+            ConfusedKanji {
+              kanji: pair.answer,
+              count: pair.count,
+            }
+          })
+          .collect_vec();
+
+        KanjiConfusions {
+          kanji,
+          quizzes: quizzes.get(&kanji).copied().unwrap_or(0),
+          mistakes: confused_with.iter().map(|it| it.count).sum(),
+          confused_with,
+        }
+      })
+      .sorted_unstable_by(|a, b| {
+        b.mistakes
+          .cmp(&a.mistakes)
+          .then_with(|| a.kanji.cmp(&b.kanji))
+      })
+      .collect();
+
+    let trend = weeks
+      .into_iter()
+      .map(|(week, (answers, mistakes))| {
+        ConfusionTrendPoint {
+          week: week.to_string(),
+          answers,
+          mistakes,
+          error_rate: (mistakes as f64) / (answers as f64),
+        }
+      })
+      .collect();
+
+    Ok(Self {
+      pairs: pairs.into_iter().take(limit).collect(),
+      kanjis,
+      trend,
+    })
+  }
+
+  pub fn pairs(&self) -> &[ConfusionPair] {
+    &self.pairs
+  }
+}
+
+#[cfg(desktop)]
+impl ConfusionPair {
+  pub fn question(&self) -> KanjiChar {
+    self.question
+  }
+
+  pub fn answer(&self) -> KanjiChar {
+    self.answer
+  }
+}
+
+#[cfg(desktop)]
+fn week_of(at: &Zoned) -> Result<Date> {
+  let date = at.date();
+  let offset = date.weekday().to_monday_zero_offset();
+  Ok(date.checked_sub(i64::from(offset).days())?)
+}
@@ -0,0 +1,243 @@
+use crate::core::snippet::Snippet;
+use crate::database::model::kanji_dictionary::KanjiDictionaryEntry;
+use crate::database::sql_types::{KanjiChar, SourceId};
+use crate::manager::ManagerExt;
+use crate::settings::DistractorStrategy;
+use anyhow::Result;
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use tauri::AppHandle;
+
+static COMPONENTS: LazyLock<HashMap<char, Vec<char>>> =
+  LazyLock::new(|| parse_components(include_str!("../../../data/components.txt")));
+
+/// Number of wrong options offered along with the answer.
+const WRONG_OPTIONS: usize = 9;
+
+/// Chooses the wrong options of the questions, according to how similar they are to the answer.
+pub(super) struct Distractors {
+  strategy: DistractorStrategy,
+  pool: Vec<KanjiChar>,
+  dictionary: HashMap<KanjiChar, KanjiDictionaryEntry>,
+
+  /// Rank of each kanji in the sources, from the most common.
+  ranks: HashMap<KanjiChar, usize>,
+
+  /// Times two kanji were mistaken for each other, keyed by the lowest of them first.
+  confusions: HashMap<(KanjiChar, KanjiChar), u32>,
+}
+
+impl Distractors {
+  pub fn load(
+    app: &AppHandle,
+    strategy: DistractorStrategy,
+    pool: Vec<KanjiChar>,
+    sources: &[SourceId],
+  ) -> Result<Self> {
+    let db = app.database();
+    let uses = |other| strategy == other || strategy == DistractorStrategy::Mixed;
+    let mut distractors = Self {
+      strategy,
+      pool,
+      dictionary: HashMap::new(),
+      ranks: HashMap::new(),
+      confusions: HashMap::new(),
+    };
+
+    if uses(DistractorStrategy::Strokes) || uses(DistractorStrategy::Reading) {
+      distractors.dictionary = db
+        .get_kanji_dictionary()?
+        .into_iter()
+        .map(|entry| (entry.id, entry))
+        .collect();
+    }
+
+    if uses(DistractorStrategy::Frequency) {
+      let mut counts: HashMap<KanjiChar, u64> = HashMap::new();
+      for (kanji, _, seen) in db.get_kanji_counts(sources)? {
+        let count = counts.entry(kanji).or_default();
+        *count = count.saturating_add(seen);
+      }
+
+      distractors.ranks = counts
+        .into_iter()
+        .sorted_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+        .enumerate()
+        .map(|(rank, (kanji, _))| (kanji, rank))
+        .collect();
+    }
+
+    if uses(DistractorStrategy::Confusion) {
+      for answer in db.get_quiz_answers()? {
+        if answer.question != answer.answer {
+          let pair = pair_of(answer.question, answer.answer);
+          *distractors
+            .confusions
+            .entry(pair)
+            .or_default() += 1;
+        }
+      }
+    }
+
+    Ok(distractors)
+  }
+
+  /// Returns the options of a question, including its answer, in random order.
+  /// Kanji of the snippet are never picked, since they could not be the missing one.
+  pub fn pick(&self, snippet: &Snippet, answer: KanjiChar) -> Vec<KanjiChar> {
+    let rng = &mut rand::rng();
+    let mut candidates = self
+      .pool
+      .iter()
+      .copied()
+      .filter(|kanji| *kanji != answer && !snippet.contains(*kanji))
+      .collect_vec();
+
+    // Shuffling first breaks the ties between equally similar kanji at random.
+    candidates.shuffle(rng);
+
+    let mut options = if self.strategy == DistractorStrategy::Random {
+      Vec::with_capacity(WRONG_OPTIONS + 1)
+    } else {
+      candidates
+        .iter()
+        .map(|kanji| (*kanji, self.similarity(answer, *kanji)))
+        .filter(|(_, similarity)| *similarity > 0.0)
+        .sorted_by(|a, b| b.1.total_cmp(&a.1))
+        .take(WRONG_OPTIONS)
+        .map(|(kanji, _)| kanji)
+        .collect_vec()
+    };
+
+    // Not enough similar kanji were found, so the rest are random.
+    let missing = WRONG_OPTIONS.saturating_sub(options.len());
+    let fill = candidates
+      .iter()
+      .filter(|kanji| !options.contains(kanji))
+      .take(missing)
+      .copied()
+      .collect_vec();
+
+    options.extend(fill);
+    options.push(answer);
+    options.shuffle(rng);
+    options
+  }
+
+  fn similarity(&self, a: KanjiChar, b: KanjiChar) -> f64 {
+    match self.strategy {
+      DistractorStrategy::Random => 0.0,
+      DistractorStrategy::Components => shared_components(a, b),
+      DistractorStrategy::Strokes => self.stroke_similarity(a, b),
+      DistractorStrategy::Frequency => self.frequency_similarity(a, b),
+      DistractorStrategy::Reading => self.shared_readings(a, b),
+      DistractorStrategy::Confusion => f64::from(self.confusions(a, b)),
+      DistractorStrategy::Mixed => {
+        let confusions = f64::from(self.confusions(a, b).min(3));
+        let readings = self.shared_readings(a, b).min(1.0);
+        3.0 * shared_components(a, b)
+          + 2.0 * readings
+          + confusions
+          + self.stroke_similarity(a, b)
+          + self.frequency_similarity(a, b)
+      }
+    }
+  }
+
+  fn confusions(&self, a: KanjiChar, b: KanjiChar) -> u32 {
+    self
+      .confusions
+      .get(&pair_of(a, b))
+      .copied()
+      .unwrap_or(0)
+  }
+
+  fn shared_readings(&self, a: KanjiChar, b: KanjiChar) -> f64 {
+    let (Some(a), Some(b)) = (self.dictionary.get(&a), self.dictionary.get(&b)) else {
+      return 0.0;
+    };
+
+    let shared = a
+      .on_readings
+      .iter()
+      .filter(|reading| b.on_readings.contains(reading))
+      .count();
+
+    shared as f64
+  }
+
+  fn stroke_similarity(&self, a: KanjiChar, b: KanjiChar) -> f64 {
+    let strokes = |kanji| {
+      self
+        .dictionary
+        .get(&kanji)
+        .and_then(|entry| entry.stroke_count)
+    };
+
+    match (strokes(a), strokes(b)) {
+      (Some(a), Some(b)) => 1.0 / f64::from(1 + a.abs_diff(b)),
+      _ => 0.0,
+    }
+  }
+
+  fn frequency_similarity(&self, a: KanjiChar, b: KanjiChar) -> f64 {
+    match (self.ranks.get(&a), self.ranks.get(&b)) {
+      (Some(a), Some(b)) => 10.0 / (10.0 + a.abs_diff(*b) as f64),
+      _ => 0.0,
+    }
+  }
+}
+
+/// Share of the components of either kanji that both have, where every kanji counts as
+/// one of its own components, so that `青` and `清` are similar too.
+fn shared_components(a: KanjiChar, b: KanjiChar) -> f64 {
+  let components = |kanji: KanjiChar| {
+    COMPONENTS
+      .get(&*kanji)
+      .into_iter()
+      .flatten()
+      .copied()
+      .chain([*kanji])
+      .collect_vec()
+  };
+
+  let (a, b) = (components(a), components(b));
+  let shared = a.iter().filter(|c| b.contains(c)).count();
+  if shared == 0 {
+    return 0.0;
+  }
+
+  let total = a.len() + b.len() - shared;
+  shared as f64 / total as f64
+}
+
+fn pair_of(a: KanjiChar, b: KanjiChar) -> (KanjiChar, KanjiChar) {
+  if a <= b { (a, b) } else { (b, a) }
+}
+
+/// Each line of the table holds a component followed by the kanji containing it.
+fn parse_components(table: &str) -> HashMap<char, Vec<char>> {
+  let mut components: HashMap<char, Vec<char>> = HashMap::new();
+  for line in table.lines() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+
+    let mut chars = line.chars().filter(|c| !c.is_whitespace());
+    let Some(component) = chars.next() else {
+      continue;
+    };
+
+    for kanji in chars {
+      components
+        .entry(kanji)
+        .or_default()
+        .push(component);
+    }
+  }
+
+  components
+}
@@ -1,6 +1,8 @@
 use crate::core::quiz::Quiz;
+use crate::core::task::Task;
 use crate::database::sql_types::KanjiChar;
 use crate::http;
+use crate::settings::DistractorStrategy;
 use anyhow::Result;
 use itertools::Itertools;
 use scraper::Html;
@@ -10,7 +12,12 @@ use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
 use url::Url;
 
-pub(super) async fn from_urls(app: AppHandle, urls: Vec<Url>) -> Result<Quiz> {
+pub(super) async fn from_urls(
+  app: AppHandle,
+  urls: Vec<Url>,
+  strategy: DistractorStrategy,
+  task: Task,
+) -> Result<Quiz> {
   let semaphore = Arc::new(Semaphore::new(3));
   let mut set: JoinSet<Result<Vec<KanjiChar>>> = urls
     .into_iter()
@@ -38,8 +45,9 @@ pub(super) async fn from_urls(app: AppHandle, urls: Vec<Url>) -> Result<Quiz> {
 
   let mut kanjis = Vec::with_capacity(set.len());
   while let Some(kanji) = set.join_next().await {
+    task.check()?;
     kanjis.extend(kanji??);
   }
 
-  Quiz::from_chunk(app, kanjis).await
+  Quiz::from_chunk(app, kanjis, strategy, task).await
 }
@@ -0,0 +1,139 @@
+use crate::database::sql_types::KanjiChar;
+use serde::Serialize;
+
+#[cfg(desktop)]
+use {
+  crate::database::sql_types::QuizConfidence,
+  crate::manager::ManagerExt,
+  crate::settings,
+  anyhow::Result,
+  itertools::Itertools,
+  std::collections::HashMap,
+  tauri::AppHandle,
+};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuizKanjiStats {
+  kanji: KanjiChar,
+  quizzes: u64,
+  correct_quiz_answers: u64,
+  quiz_accuracy: f64,
+
+  /// Average time taken by the timed correct answers, in milliseconds.
+  average_duration: Option<f64>,
+
+  /// Correct answers given with a low confidence.
+  unsure_answers: u64,
+
+  /// Whether the kanji is mostly answered correctly, but much slower than the others.
+  slow: bool,
+
+  #[serde(skip)]
+  total_duration: i64,
+
+  #[serde(skip)]
+  timed_answers: u64,
+}
+
+#[cfg(desktop)]
+impl QuizKanjiStats {
+  fn new(kanji: KanjiChar) -> Self {
+    Self {
+      kanji,
+      quizzes: 0,
+      correct_quiz_answers: 0,
+      quiz_accuracy: 0.0,
+      average_duration: None,
+      unsure_answers: 0,
+      slow: false,
+      total_duration: 0,
+      timed_answers: 0,
+    }
+  }
+
+  /// Returns the stats of every kanji that was quizzed, from the slowest to the fastest.
+  pub fn load(app: &AppHandle) -> Result<Vec<Self>> {
+    let threshold = slow_threshold(app)?;
+    let mut stats: HashMap<KanjiChar, Self> = HashMap::new();
+
+    for answer in app.database().get_quiz_answers()? {
+      let kanji = stats
+        .entry(answer.question)
+        .or_insert_with(|| Self::new(answer.question));
+
+      kanji.quizzes += 1;
+
+      if answer.question == answer.answer {
+        kanji.correct_quiz_answers += 1;
+
+        if let Some(duration) = answer.duration {
+          kanji.total_duration = kanji.total_duration.saturating_add(duration);
+          kanji.timed_answers += 1;
+        }
+
+        if answer.confidence == Some(QuizConfidence::Low) {
+          kanji.unsure_answers += 1;
+        }
+      }
+    }
+
+    let stats = stats
+      .into_values()
+      .update(|kanji| {
+        kanji.quiz_accuracy = (kanji.correct_quiz_answers as f64) / (kanji.quizzes as f64);
+        kanji.average_duration = (kanji.timed_answers > 0)
+          .then(|| (kanji.total_duration as f64) / (kanji.timed_answers as f64));
+
+        kanji.slow = kanji.quiz_accuracy >= 0.5
+          && threshold.is_some_and(|threshold| {
+            kanji
+              .average_duration
+              .is_some_and(|duration| duration > threshold)
+          });
+      })
+      .sorted_by(|a, b| {
+        b.average_duration
+          .unwrap_or_default()
+          .total_cmp(&a.average_duration.unwrap_or_default())
+          .then_with(|| a.kanji.cmp(&b.kanji))
+      })
+      .collect();
+
+    Ok(stats)
+  }
+
+  pub fn kanji(&self) -> KanjiChar {
+    self.kanji
+  }
+
+  pub fn is_slow(&self) -> bool {
+    self.slow
+  }
+
+  pub fn total_duration(&self) -> i64 {
+    self.total_duration
+  }
+
+  pub fn timed_answers(&self) -> u64 {
+    self.timed_answers
+  }
+}
+
+/// Returns whether a correct answer took much longer than the correct answers usually do.
+#[cfg(desktop)]
+pub fn is_slow_answer(app: &AppHandle, duration: u64) -> Result<bool> {
+  let threshold = slow_threshold(app)?;
+  Ok(threshold.is_some_and(|threshold| duration as f64 > threshold))
+}
+
+/// Duration in milliseconds above which a correct answer counts as slow, which is
+/// a multiple of the average of all the timed correct answers.
+#[cfg(desktop)]
+fn slow_threshold(app: &AppHandle) -> Result<Option<f64>> {
+  let average = app
+    .database()
+    .get_average_correct_quiz_duration()?;
+
+  Ok(average.map(|average| average * settings::slow_answer_ratio(app)))
+}
@@ -1,12 +1,23 @@
+mod confusion;
+mod kanji_stats;
+mod session;
 mod source_stats;
 
 #[cfg(desktop)]
 mod chunk;
 #[cfg(desktop)]
+mod distractor;
+#[cfg(desktop)]
 mod html;
 
+pub use confusion::QuizConfusions;
+pub use kanji_stats::QuizKanjiStats;
+pub use session::{QuizSessionState, QuizSessionSummary};
 pub use source_stats::QuizSourceStats;
 
+#[cfg(desktop)]
+pub use kanji_stats::is_slow_answer;
+
 use crate::core::snippet::Snippet;
 use crate::database::sql_types::{KanjiChar, SourceGroupId, SourceId};
 use serde::{Deserialize, Serialize};
@@ -15,8 +26,11 @@ use url::Url;
 #[cfg(desktop)]
 use {
   crate::core::kanji::blocking_search_with_options,
+  crate::core::kanji_status::KanjiStatuses,
+  crate::core::review,
+  crate::core::task::Task,
   crate::manager::ManagerExt,
-  crate::settings,
+  crate::settings::{self, DistractorStrategy},
   anyhow::{Error, Result, bail},
   itertools::Itertools,
   rand::seq::{IndexedRandom, IteratorRandom},
@@ -32,43 +46,123 @@ pub struct Quiz(Vec<QuizQuestion>);
 
 #[cfg(desktop)]
 impl Quiz {
-  pub async fn new(app: AppHandle, kind: QuizKind) -> Result<Self> {
+  /// Builds the questions of the quiz, choosing their wrong options with the strategy,
+  /// except for [`QuizKind::Confused`], which always uses [`DistractorStrategy::Confusion`].
+  pub async fn new(
+    app: AppHandle,
+    kind: QuizKind,
+    strategy: DistractorStrategy,
+    task: Task,
+  ) -> Result<Self> {
     match kind {
-      QuizKind::Chunk { chunk } => Self::from_chunk(app, chunk).await,
-      QuizKind::RandomChunk => Self::from_random_chunk(app).await,
-      QuizKind::Source { ids } => Self::from_sources(app, ids).await,
-      QuizKind::RandomSource => Self::from_random_source(app).await,
-      QuizKind::SourceGroup { ids } => Self::from_source_groups(app, ids).await,
-      QuizKind::RandomSourceGroup => Self::from_random_source_group(app).await,
-      QuizKind::Url { urls } => html::from_urls(app, urls).await,
+      QuizKind::Chunk { chunk } => Self::from_chunk(app, chunk, strategy, task).await,
+      QuizKind::RandomChunk => Self::from_random_chunk(app, strategy, task).await,
+      QuizKind::Source { ids } => Self::from_sources(app, ids, strategy, task).await,
+      QuizKind::RandomSource => Self::from_random_source(app, strategy, task).await,
+      QuizKind::SourceGroup { ids } => Self::from_source_groups(app, ids, strategy, task).await,
+      QuizKind::RandomSourceGroup => Self::from_random_source_group(app, strategy, task).await,
+      QuizKind::Url { urls } => html::from_urls(app, urls, strategy, task).await,
+      QuizKind::Due => Self::from_due(app, strategy, task).await,
+      QuizKind::Slow => Self::from_slow(app, strategy, task).await,
+      QuizKind::Confused => Self::from_confused(app, task).await,
     }
   }
 
-  async fn from_chunk(app: AppHandle, kanjis: Vec<KanjiChar>) -> Result<Self> {
+  async fn from_chunk(
+    app: AppHandle,
+    kanjis: Vec<KanjiChar>,
+    strategy: DistractorStrategy,
+    task: Task,
+  ) -> Result<Self> {
     let sources = app.database().get_enabled_sources()?;
-    chunk::with_sources(app, kanjis, sources).await
+    chunk::with_sources(app, kanjis, sources, strategy, task).await
   }
 
-  async fn from_random_chunk(app: AppHandle) -> Result<Self> {
+  async fn from_random_chunk(
+    app: AppHandle,
+    strategy: DistractorStrategy,
+    task: Task,
+  ) -> Result<Self> {
     let chunk_size = settings::set_chunk_size(&app);
-    let kanjis = app
-      .database()
+    let db = app.database();
+    let statuses = KanjiStatuses::load(&app)?;
+    let kanjis = db
       .get_kanji_chars()?
-      .sample(&mut rand::rng(), chunk_size)
-      .copied()
+      .into_iter()
+      .filter(|kanji| !statuses.is_skipped(*kanji))
+      .sample(&mut rand::rng(), chunk_size);
+
+    Self::from_chunk(app, kanjis, strategy, task).await
+  }
+
+  async fn from_due(app: AppHandle, strategy: DistractorStrategy, task: Task) -> Result<Self> {
+    let chunk_size = settings::set_chunk_size(&app);
+    let statuses = KanjiStatuses::load(&app)?;
+    let kanjis = review::due(&app)?
+      .into_iter()
+      .filter(|kanji| !statuses.is_skipped(*kanji))
+      .take(chunk_size)
+      .collect_vec();
+
+    if kanjis.is_empty() {
+      bail!("No kanji is due for review");
+    }
+
+    Self::from_chunk(app, kanjis, strategy, task).await
+  }
+
+  async fn from_slow(app: AppHandle, strategy: DistractorStrategy, task: Task) -> Result<Self> {
+    let chunk_size = settings::set_chunk_size(&app);
+    let statuses = KanjiStatuses::load(&app)?;
+    let kanjis = QuizKanjiStats::load(&app)?
+      .into_iter()
+      .filter(QuizKanjiStats::is_slow)
+      .map(|stats| stats.kanji())
+      .filter(|kanji| !statuses.is_skipped(*kanji))
+      .take(chunk_size)
+      .collect_vec();
+
+    if kanjis.is_empty() {
+      bail!("No kanji is slow to answer");
+    }
+
+    Self::from_chunk(app, kanjis, strategy, task).await
+  }
+
+  async fn from_confused(app: AppHandle, task: Task) -> Result<Self> {
+    let chunk_size = settings::set_chunk_size(&app);
+    let statuses = KanjiStatuses::load(&app)?;
+    let kanjis = QuizConfusions::load(&app, chunk_size)?
+      .pairs()
+      .iter()
+      .flat_map(|pair| [pair.question(), pair.answer()])
+      .filter(|kanji| !statuses.is_skipped(*kanji))
+      .unique()
+      .take(chunk_size)
       .collect_vec();
 
-    Self::from_chunk(app, kanjis).await
+    if kanjis.is_empty() {
+      bail!("No kanji was ever mistaken for another");
+    }
+
+    Self::from_chunk(app, kanjis, DistractorStrategy::Confusion, task).await
   }
 
-  async fn from_sources(app: AppHandle, ids: Vec<SourceId>) -> Result<Self> {
+  async fn from_sources(
+    app: AppHandle,
+    ids: Vec<SourceId>,
+    strategy: DistractorStrategy,
+    task: Task,
+  ) -> Result<Self> {
     let sources = app.database().get_sources_by(&ids)?;
     let stats = spawn_blocking({
       let app = app.clone();
       let sources = sources.clone();
+      let task = task.clone();
       move || {
         blocking_search_with_options(&app)
           .sources(&sources)
+          .task(&task)
           .call()
       }
     });
@@ -77,13 +171,18 @@ impl Quiz {
     let kanjis = stats
       .await??
       .into_iter()
+      .filter(|stat| !stat.status().is_skipped())
       .map(|stat| stat.character())
       .sample(&mut rand::rng(), chunk_size);
 
-    chunk::with_sources(app, kanjis, sources).await
+    chunk::with_sources(app, kanjis, sources, strategy, task).await
   }
 
-  async fn from_random_source(app: AppHandle) -> Result<Self> {
+  async fn from_random_source(
+    app: AppHandle,
+    strategy: DistractorStrategy,
+    task: Task,
+  ) -> Result<Self> {
     let Some(id) = app
       .database()
       .get_source_ids()?
@@ -93,10 +192,15 @@ impl Quiz {
       bail!("No source found");
     };
 
-    Self::from_sources(app, vec![id]).await
+    Self::from_sources(app, vec![id], strategy, task).await
   }
 
-  async fn from_source_groups(app: AppHandle, ids: Vec<SourceGroupId>) -> Result<Self> {
+  async fn from_source_groups(
+    app: AppHandle,
+    ids: Vec<SourceGroupId>,
+    strategy: DistractorStrategy,
+    task: Task,
+  ) -> Result<Self> {
     let sources = spawn_blocking({
       let app = app.clone();
       move || {
@@ -114,10 +218,14 @@ impl Quiz {
       }
     });
 
-    Self::from_sources(app, sources.await??).await
+    Self::from_sources(app, sources.await??, strategy, task).await
   }
 
-  async fn from_random_source_group(app: AppHandle) -> Result<Self> {
+  async fn from_random_source_group(
+    app: AppHandle,
+    strategy: DistractorStrategy,
+    task: Task,
+  ) -> Result<Self> {
     let Some(id) = app
       .database()
       .get_source_group_ids()?
@@ -127,23 +235,42 @@ impl Quiz {
       bail!("No source group found");
     };
 
-    Self::from_source_groups(app, vec![id]).await
+    Self::from_source_groups(app, vec![id], strategy, task).await
   }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "kind", rename_all = "kebab-case")]
 pub enum QuizKind {
-  Chunk { chunk: Vec<KanjiChar> },
+  Chunk {
+    chunk: Vec<KanjiChar>,
+  },
   RandomChunk,
-  Source { ids: Vec<SourceId> },
+  Source {
+    ids: Vec<SourceId>,
+  },
   RandomSource,
-  SourceGroup { ids: Vec<SourceGroupId> },
+  SourceGroup {
+    ids: Vec<SourceGroupId>,
+  },
   RandomSourceGroup,
-  Url { urls: Vec<Url> },
+  Url {
+    urls: Vec<Url>,
+  },
+
+  /// Kanji whose review is due, from the most overdue.
+  Due,
+
+  /// Kanji that are mostly answered correctly, but slowly, from the slowest.
+  Slow,
+
+  /// Kanji most often mistaken for each other. Their options are always chosen by past
+  /// confusions, whatever the strategy asked for, so the kanji they were mistaken for come
+  /// first, unless they appear in the snippet or there are more of them than options.
+  Confused,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QuizQuestion {
   snippet: Snippet,
@@ -0,0 +1,219 @@
+use crate::core::quiz::{QuizKind, QuizQuestion};
+use crate::database::model::quiz_answer::QuizAnswer;
+use crate::database::sql_types::{KanjiChar, QuizSessionId, Zoned};
+use serde::Serialize;
+
+#[cfg(desktop)]
+use {
+  crate::core::quiz::Quiz,
+  crate::core::task::Task,
+  crate::database::model::quiz_session::{NewQuizSession, QuizSession},
+  crate::manager::ManagerExt,
+  crate::settings::DistractorStrategy,
+  anyhow::{Result, bail},
+  itertools::Itertools,
+  std::collections::HashMap,
+  tauri::AppHandle,
+};
+
+/// Quiz whose questions and answers are kept, so that it can be resumed on another device.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuizSessionState {
+  id: QuizSessionId,
+  kind: QuizKind,
+  questions: Vec<QuizQuestion>,
+  answers: Vec<QuizAnswer>,
+
+  /// Index of the first question without an answer, which is the number of questions
+  /// once all of them were answered.
+  next: usize,
+
+  started_at: Zoned,
+  finished_at: Option<Zoned>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuizSessionSummary {
+  id: QuizSessionId,
+  kind: QuizKind,
+  questions: usize,
+  answered: usize,
+  correct: usize,
+
+  /// Share of the questions answered correctly, from 0 to 1.
+  score: f64,
+
+  /// Time taken by the timed answers, in milliseconds.
+  duration: i64,
+
+  mistakes: Vec<QuizSessionMistake>,
+  started_at: Zoned,
+  finished_at: Option<Zoned>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuizSessionMistake {
+  question: KanjiChar,
+  answer: KanjiChar,
+  censored: String,
+}
+
+#[cfg(desktop)]
+impl QuizSessionState {
+  /// Builds a quiz and keeps it as a new session.
+  pub async fn start(
+    app: AppHandle,
+    kind: QuizKind,
+    strategy: DistractorStrategy,
+    task: Task,
+  ) -> Result<Self> {
+    let quiz = Quiz::new(app.clone(), kind.clone(), strategy, task).await?;
+
+    // Answers are matched to questions by their kanji, so each may only be asked once.
+    let questions = quiz
+      .0
+      .into_iter()
+      .unique_by(|question| question.answer)
+      .collect_vec();
+
+    let id = NewQuizSession::builder()
+      .kind(serde_json::to_string(&kind)?)
+      .questions(serde_json::to_string(&questions)?)
+      .build()
+      .create(&app)?;
+
+    Self::load(&app, id)
+  }
+
+  /// Loads the session, or the last unfinished one if none is given.
+  pub fn resume(app: &AppHandle, id: Option<QuizSessionId>) -> Result<Self> {
+    if let Some(id) = id {
+      return Self::load(app, id);
+    }
+
+    let Some(session) = app
+      .database()
+      .get_last_unfinished_quiz_session()?
+    else {
+      bail!("No quiz session to resume");
+    };
+
+    Self::load(app, session.id)
+  }
+
+  pub fn load(app: &AppHandle, id: QuizSessionId) -> Result<Self> {
+    let db = app.database();
+    let session = db.get_quiz_session(id)?;
+    let answers = db.get_quiz_session_answers(id)?;
+    let (kind, mut questions) = parse(&session)?;
+    for question in &mut questions {
+      question.snippet.refresh(app)?;
+    }
+
+    let answered = first_answers(&answers);
+    let next = questions
+      .iter()
+      .position(|question| !answered.contains_key(&question.answer))
+      .unwrap_or(questions.len());
+
+    Ok(Self {
+      id,
+      kind,
+      questions,
+      answers,
+      next,
+      started_at: session.started_at,
+      finished_at: session.finished_at,
+    })
+  }
+
+  /// Finishes the session once each of its questions has been answered.
+  pub fn finish_if_complete(app: &AppHandle, id: QuizSessionId) -> Result<()> {
+    let db = app.database();
+    if db.count_quiz_session_answered(id)? >= db.count_quiz_session_questions(id)? {
+      db.finish_quiz_session(id)?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(desktop)]
+impl QuizSessionSummary {
+  pub fn load(app: &AppHandle, id: QuizSessionId) -> Result<Self> {
+    let db = app.database();
+    let session = db.get_quiz_session(id)?;
+    let answers = db.get_quiz_session_answers(id)?;
+    let (kind, questions) = parse(&session)?;
+
+    let first = first_answers(&answers);
+    let mut answered = 0;
+    let mut correct = 0;
+    let mut duration = 0i64;
+    let mut mistakes = Vec::new();
+
+    for question in &questions {
+      let Some(answer) = first.get(&question.answer) else {
+        continue;
+      };
+
+      answered += 1;
+      if let Some(it) = answer.duration {
+        duration = duration.saturating_add(it);
+      }
+
+      if answer.answer == question.answer {
+        correct += 1;
+      } else {
+        mistakes.push(QuizSessionMistake {
+          question: question.answer,
+          answer: answer.answer,
+          censored: question.censored.clone(),
+        });
+      }
+    }
+
+    let score = if questions.is_empty() {
+      0.0
+    } else {
+      (correct as f64) / (questions.len() as f64)
+    };
+
+    Ok(Self {
+      id,
+      kind,
+      questions: questions.len(),
+      answered,
+      correct,
+      score,
+      duration,
+      mistakes,
+      started_at: session.started_at,
+      finished_at: session.finished_at,
+    })
+  }
+}
+
+#[cfg(desktop)]
+fn parse(session: &QuizSession) -> Result<(QuizKind, Vec<QuizQuestion>)> {
+  let kind = serde_json::from_str(&session.kind)?;
+  let questions = serde_json::from_str(&session.questions)?;
+  Ok((kind, questions))
+}
+
+/// Only the first answer to each question counts, since the others were given after
+/// the right one was shown.
+#[cfg(desktop)]
+fn first_answers(answers: &[QuizAnswer]) -> HashMap<KanjiChar, &QuizAnswer> {
+  let mut first = HashMap::new();
+  for answer in answers {
+    first
+      .entry(answer.question)
+      .or_insert(answer);
+  }
+
+  first
+}
@@ -0,0 +1,347 @@
+use crate::core::csv::split_fields;
+use crate::core::kanji::{self, KanjiStats};
+use crate::core::task::Task;
+use crate::core::{encoding, variant};
+use crate::database::model::reference_kanji::ReferenceKanji;
+use crate::database::sql_types::KanjiChar;
+use crate::manager::ManagerExt;
+use crate::settings;
+use anyhow::{Result, bail};
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use tauri::async_runtime::spawn_blocking;
+
+/// Added to both ratios before comparing them, so that kanji missing from either side
+/// still get a finite deviation.
+const SMOOTHING: f64 = 1e-5;
+
+/// Frequencies of a reference corpus, such as newspapers or novels.
+#[derive(Default)]
+pub struct Reference {
+  kanjis: HashMap<KanjiChar, ReferenceEntry>,
+  total: f64,
+}
+
+#[derive(Clone, Copy)]
+struct ReferenceEntry {
+  seen: u64,
+  rank: u32,
+}
+
+impl Reference {
+  /// Loads the imported reference list, following the settings on iteration marks and variants.
+  pub fn load(app: &AppHandle) -> Result<Self> {
+    let iteration_marks = settings::iteration_marks(app);
+    let normalize_variants = settings::normalize_variants(app);
+    let mut counts: HashMap<KanjiChar, u64> = HashMap::new();
+
+    for kanji in app.database().get_reference_kanjis()? {
+      let Some(character) = variant::fold(kanji.id, iteration_marks, normalize_variants) else {
+        continue;
+      };
+
+      let seen = u64::try_from(kanji.seen).unwrap_or(0);
+      let count = counts.entry(character).or_default();
+      *count = count.saturating_add(seen);
+    }
+
+    let total = counts
+      .values()
+      .copied()
+      .fold(0u64, u64::saturating_add) as f64;
+
+    let kanjis = counts
+      .into_iter()
+      .sorted_by(|(a, a_seen), (b, b_seen)| b_seen.cmp(a_seen).then(a.cmp(b)))
+      .zip(1u32..)
+      .map(|((character, seen), rank)| (character, ReferenceEntry { seen, rank }))
+      .collect();
+
+    Ok(Self { kanjis, total })
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.kanjis.is_empty()
+  }
+
+  pub fn characters(&self) -> impl Iterator<Item = KanjiChar> {
+    self.kanjis.keys().copied()
+  }
+
+  /// Rank of the kanji in the reference list, starting from 1 for the most common one.
+  pub fn rank(&self, kanji: KanjiChar) -> Option<u32> {
+    self
+      .kanjis
+      .get(&kanji)
+      .map(|entry| entry.rank)
+  }
+
+  /// Ratio of the kanji in the reference list, which is zero if it is missing from it.
+  pub fn ratio(&self, kanji: KanjiChar) -> f64 {
+    match self.kanjis.get(&kanji) {
+      Some(entry) if self.total.is_normal() => (entry.seen as f64) / self.total,
+      _ => 0.0,
+    }
+  }
+}
+
+/// Base 2 logarithm of how much more often the kanji is seen in our sources than in the
+/// reference, so that 1 means twice as often and -1 means half as often.
+pub fn deviation(ratio: f64, reference_ratio: f64) -> f64 {
+  ((ratio + SMOOTHING) / (reference_ratio + SMOOTHING)).log2()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrequencyDivergences {
+  /// Kanji seen more often in our sources than in the reference, most over-represented first.
+  over: Vec<FrequencyDivergence>,
+
+  /// Kanji seen less often in our sources than in the reference, most under-represented first.
+  under: Vec<FrequencyDivergence>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrequencyDivergence {
+  character: KanjiChar,
+  seen: u32,
+  ratio: f64,
+  reference_rank: Option<u32>,
+  reference_ratio: f64,
+  deviation: f64,
+}
+
+/// Compares the kanji of the enabled sources against the reference list, including the kanji
+/// of the reference that were never seen.
+pub async fn divergences(app: AppHandle, limit: usize, task: Task) -> Result<FrequencyDivergences> {
+  let stats = kanji::search(app.clone(), task).await?;
+  let reference = spawn_blocking(move || Reference::load(&app)).await??;
+  if reference.is_empty() {
+    bail!("No reference frequency list was imported");
+  }
+
+  let seen: HashMap<KanjiChar, &KanjiStats> = stats
+    .iter()
+    .map(|kanji| (kanji.character(), kanji))
+    .collect();
+
+  let mut divergences = reference
+    .characters()
+    .chain(seen.keys().copied().collect_vec())
+    .unique()
+    .map(|character| {
+      let (count, ratio) = seen
+        .get(&character)
+        .map_or((0, 0.0), |kanji| (kanji.seen(), kanji.ratio()));
+
+      let reference_ratio = reference.ratio(character);
+      FrequencyDivergence {
+        character,
+        seen: count,
+        ratio,
+        reference_rank: reference.rank(character),
+        reference_ratio,
+        deviation: deviation(ratio, reference_ratio),
+      }
+    })
+    .collect_vec();
+
+  divergences.sort_by(|a, b| b.deviation.total_cmp(&a.deviation));
+
+  let over = divergences
+    .iter()
+    .take_while(|divergence| divergence.deviation > 0.0)
+    .take(limit)
+    .count();
+
+  let under = divergences
+    .iter()
+    .rev()
+    .take_while(|divergence| divergence.deviation < 0.0)
+    .take(limit)
+    .count();
+
+  let mut under = divergences.split_off(divergences.len() - under);
+  under.reverse();
+  divergences.truncate(over);
+
+  Ok(FrequencyDivergences { over: divergences, under })
+}
+
+/// Replaces the reference list with a CSV or TSV file of kanji and counts, returning how many
+/// kanji it has. Lines that do not start with a kanji, such as headers, are skipped, while
+/// a kanji followed by anything but a count fails the import. The counts of repeated kanji
+/// are added up.
+pub async fn import(app: AppHandle, path: PathBuf) -> Result<usize> {
+  spawn_blocking(move || {
+    let text = encoding::decode(&fs::read(&path)?, None)?;
+    let kanjis = parse(&text)?;
+    if kanjis.is_empty() {
+      bail!("No kanji were found in {}", path.display());
+    }
+
+    app
+      .database()
+      .replace_reference_kanjis(&kanjis)
+  })
+  .await?
+}
+
+fn parse(text: &str) -> Result<Vec<ReferenceKanji>> {
+  let rows = text
+    .lines()
+    .enumerate()
+    .filter_map(|(index, line)| {
+      let delimiter = delimiter_of(line);
+      let fields = split_fields(line, delimiter);
+      let [kanji, count, ..] = fields.as_slice() else {
+        return None;
+      };
+
+      let mut chars = kanji.chars();
+      let (Some(kanji), None) = (chars.next().and_then(KanjiChar::from_char), chars.next()) else {
+        return None;
+      };
+
+      let row = Row {
+        number: index + 1,
+        line,
+        kanji,
+        count: count.clone(),
+        columns: fields.len(),
+        comma: delimiter == ',',
+      };
+
+      Some(row)
+    })
+    .collect_vec();
+
+  // An unquoted count such as `1,234` would otherwise be read as 1, which can only be told
+  // apart from a further column by the line having more of them than the rest of the file.
+  let columns = rows
+    .iter()
+    .filter(|row| row.comma)
+    .counts_by(|row| row.columns)
+    .into_iter()
+    .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+    .map_or(0, |(columns, _)| columns);
+
+  let mut counts: HashMap<KanjiChar, i64> = HashMap::new();
+  for row in rows {
+    if row.comma && row.columns > columns {
+      bail!(
+        "Ambiguous count on line {}, which should be quoted: {}",
+        row.number,
+        row.line
+      );
+    }
+
+    let Ok(count) = row
+      .count
+      .replace(['_', ',', ' '], "")
+      .parse::<i64>()
+    else {
+      bail!("Invalid count on line {}: {}", row.number, row.line);
+    };
+
+    if count >= 0 {
+      let seen = counts.entry(row.kanji).or_default();
+      *seen = seen.saturating_add(count);
+    }
+  }
+
+  let kanjis = counts
+    .into_iter()
+    .map(|(id, seen)| ReferenceKanji { id, seen })
+    .collect();
+
+  Ok(kanjis)
+}
+
+/// Line of the file that starts with a kanji, of which only the first two columns are read.
+struct Row<'a> {
+  number: usize,
+  line: &'a str,
+  kanji: KanjiChar,
+  count: String,
+  columns: usize,
+
+  /// Whether the columns are separated by commas, which may also appear in unquoted counts.
+  comma: bool,
+}
+
+/// Guesses the delimiter of a line, preferring tabs and semicolons, since commas may
+/// also appear in quoted counts.
+fn delimiter_of(line: &str) -> char {
+  ['\t', ';']
+    .into_iter()
+    .find(|delimiter| line.contains(*delimiter))
+    .unwrap_or(',')
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn counts(text: &str) -> Vec<(char, i64)> {
+    parse(text)
+      .unwrap()
+      .into_iter()
+      .map(|kanji| (*kanji.id, kanji.seen))
+      .sorted()
+      .collect()
+  }
+
+  #[test]
+  fn skips_headers_and_other_lines() {
+    let text = "kanji,count\n日,10\n\nhello,3\n月,5\n";
+    assert_eq!(counts(text), [('日', 10), ('月', 5)]);
+  }
+
+  #[test]
+  fn reads_tabs_and_semicolons() {
+    assert_eq!(counts("日\t1,234\n月\t5\n"), [('日', 1234), ('月', 5)]);
+    assert_eq!(counts("日;1 234\n月;5\n"), [('日', 1234), ('月', 5)]);
+  }
+
+  #[test]
+  fn reads_quoted_counts() {
+    assert_eq!(counts("日,\"1,234\"\n月,5\n"), [('日', 1234), ('月', 5)]);
+  }
+
+  #[test]
+  fn ignores_further_columns() {
+    let text = "日,12345,100\n月,5,2\n";
+    assert_eq!(counts(text), [('日', 12345), ('月', 5)]);
+  }
+
+  #[test]
+  fn adds_up_repeated_kanji() {
+    assert_eq!(counts("日,3\n月,5\n日,4\n"), [('日', 7), ('月', 5)]);
+  }
+
+  #[test]
+  fn skips_negative_counts() {
+    assert_eq!(counts("日,-3\n月,5\n"), [('月', 5)]);
+  }
+
+  #[test]
+  fn rejects_unquoted_thousands() {
+    let err = parse("日,5\n月,1,234\n火,7\n").unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Ambiguous count on line 2, which should be quoted: 月,1,234"
+    );
+  }
+
+  #[test]
+  fn rejects_invalid_counts() {
+    let err = parse("日,5\n月,many\n").unwrap_err();
+    assert_eq!(err.to_string(), "Invalid count on line 2: 月,many");
+  }
+}
@@ -0,0 +1,228 @@
+use crate::database::model::kanji_review::KanjiReview;
+use crate::database::sql_types::{KanjiChar, QuizConfidence, Zoned};
+use crate::manager::ManagerExt;
+use crate::settings::{self, ReviewParams};
+use anyhow::Result;
+use itertools::Itertools;
+use jiff::SignedDuration;
+use tauri::AppHandle;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grade {
+  /// The answer was wrong.
+  Again,
+
+  /// The answer was correct, but slow or unsure.
+  Hard,
+  Good,
+
+  /// The answer was correct, quick and given with a high confidence.
+  Easy,
+}
+
+impl Grade {
+  pub fn new(correct: bool, slow: bool, confidence: Option<QuizConfidence>) -> Self {
+    match (correct, slow, confidence) {
+      (false, ..) => Self::Again,
+      (true, true, _) | (true, _, Some(QuizConfidence::Low)) => Self::Hard,
+      (true, false, Some(QuizConfidence::High)) => Self::Easy,
+      (true, false, _) => Self::Good,
+    }
+  }
+}
+
+/// Updates the review schedule of the kanji after a quiz answer. Answers given before the
+/// review is due only count when they are wrong, so that quizzing a kanji several times
+/// in a row does not push its next review further away each time.
+pub fn record(app: &AppHandle, kanji: KanjiChar, grade: Grade) -> Result<()> {
+  let db = app.database();
+  let previous = db.get_kanji_review(kanji)?;
+  let now = Zoned::now();
+
+  if grade != Grade::Again
+    && let Some(previous) = &previous
+    && *previous.due_at > *now
+  {
+    return Ok(());
+  }
+
+  let params = settings::review_params(app);
+  let review = schedule(kanji, previous, grade, &params, now)?;
+  db.set_kanji_review(&review)
+}
+
+/// Returns the kanji whose review is due, from the most to the least overdue.
+pub fn due(app: &AppHandle) -> Result<Vec<KanjiChar>> {
+  let now = Zoned::now();
+  let kanjis = app
+    .database()
+    .get_kanji_reviews()?
+    .into_iter()
+    .filter(|review| *review.due_at <= *now)
+    .sorted_by(|a, b| (*a.due_at).cmp(&*b.due_at))
+    .map(|review| review.id)
+    .collect();
+
+  Ok(kanjis)
+}
+
+fn schedule(
+  kanji: KanjiChar,
+  previous: Option<KanjiReview>,
+  grade: Grade,
+  params: &ReviewParams,
+  now: Zoned,
+) -> Result<KanjiReview> {
+  let mut review = previous.unwrap_or_else(|| {
+    KanjiReview {
+      id: kanji,
+      ease: params.starting_ease,
+      interval: 0.0,
+      repetitions: 0,
+      lapses: 0,
+      due_at: now.clone(),
+      reviewed_at: now.clone(),
+    }
+  });
+
+  if grade == Grade::Again {
+    if review.repetitions > 0 {
+      review.lapses = review.lapses.saturating_add(1);
+    }
+
+    review.ease -= params.ease_penalty;
+    review.interval = params.first_interval;
+    review.repetitions = 0;
+  } else {
+    review.interval = match (review.repetitions, grade) {
+      (0, _) => params.first_interval,
+      (1, _) => params.second_interval,
+      (_, Grade::Hard) => review.interval * params.hard_interval * params.interval_modifier,
+      (_, Grade::Easy) => {
+        review.interval * review.ease * params.easy_bonus * params.interval_modifier
+      }
+      _ => review.interval * review.ease * params.interval_modifier,
+    };
+
+    match grade {
+      Grade::Hard => review.ease -= params.ease_step,
+      Grade::Easy => review.ease += params.ease_step,
+      _ => {}
+    }
+
+    review.repetitions = review.repetitions.saturating_add(1);
+  }
+
+  review.ease = review.ease.max(params.minimum_ease);
+  review.interval = review
+    .interval
+    .clamp(0.0, params.maximum_interval.max(0.0));
+
+  let interval = SignedDuration::try_from_secs_f64(review.interval * SECONDS_PER_DAY)?;
+  review.due_at = Zoned::from(now.checked_add(interval)?);
+  review.reviewed_at = now;
+
+  Ok(review)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn review(previous: Option<KanjiReview>, grade: Grade, params: &ReviewParams) -> KanjiReview {
+    let kanji = KanjiChar::from_char('日').unwrap();
+    schedule(kanji, previous, grade, params, Zoned::now()).unwrap()
+  }
+
+  fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+  }
+
+  #[test]
+  fn grades_answers() {
+    assert_eq!(
+      Grade::new(false, false, Some(QuizConfidence::High)),
+      Grade::Again
+    );
+    assert_eq!(
+      Grade::new(true, true, Some(QuizConfidence::High)),
+      Grade::Hard
+    );
+    assert_eq!(
+      Grade::new(true, false, Some(QuizConfidence::Low)),
+      Grade::Hard
+    );
+    assert_eq!(
+      Grade::new(true, false, Some(QuizConfidence::Medium)),
+      Grade::Good
+    );
+    assert_eq!(Grade::new(true, false, None), Grade::Good);
+    assert_eq!(
+      Grade::new(true, false, Some(QuizConfidence::High)),
+      Grade::Easy
+    );
+  }
+
+  #[test]
+  fn grows_intervals() {
+    let params = ReviewParams::default();
+    let first = review(None, Grade::Good, &params);
+    assert_close(first.interval, 1.0);
+    assert_close(first.ease, 2.5);
+    assert_eq!(first.repetitions, 1);
+
+    let due = first
+      .due_at
+      .duration_since(&first.reviewed_at);
+    assert_eq!(due, SignedDuration::from_hours(24));
+
+    let second = review(Some(first), Grade::Good, &params);
+    assert_close(second.interval, 6.0);
+
+    let third = review(Some(second.clone()), Grade::Good, &params);
+    assert_close(third.interval, 15.0);
+    assert_eq!(third.repetitions, 3);
+
+    let hard = review(Some(second.clone()), Grade::Hard, &params);
+    assert_close(hard.interval, 7.2);
+    assert_close(hard.ease, 2.35);
+
+    let easy = review(Some(second), Grade::Easy, &params);
+    assert_close(easy.interval, 19.5);
+    assert_close(easy.ease, 2.65);
+  }
+
+  #[test]
+  fn resets_forgotten_kanji() {
+    let params = ReviewParams::default();
+    let new = review(None, Grade::Again, &params);
+    assert_eq!(new.lapses, 0);
+
+    let passed = review(None, Grade::Good, &params);
+    let passed = review(Some(passed), Grade::Good, &params);
+    let forgotten = review(Some(passed), Grade::Again, &params);
+    assert_close(forgotten.interval, 1.0);
+    assert_close(forgotten.ease, 2.3);
+    assert_eq!(forgotten.repetitions, 0);
+    assert_eq!(forgotten.lapses, 1);
+  }
+
+  #[test]
+  fn bounds_ease_and_interval() {
+    let params = ReviewParams {
+      minimum_ease: 2.4,
+      maximum_interval: 10.0,
+      ..ReviewParams::default()
+    };
+
+    let first = review(None, Grade::Again, &params);
+    assert_close(first.ease, 2.4);
+
+    let second = review(Some(first), Grade::Good, &params);
+    let third = review(Some(second), Grade::Good, &params);
+    let fourth = review(Some(third), Grade::Good, &params);
+    assert_close(fourth.interval, 10.0);
+  }
+}
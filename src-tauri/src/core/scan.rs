@@ -0,0 +1,286 @@
+use crate::core::snippet::{Ruby, SnippetLocation, Token};
+use crate::core::source::walk_source;
+use crate::core::subtitle::{self, Cue};
+use crate::core::task::Task;
+use crate::core::tokenizer::{self, Tokenizer};
+use crate::core::{aozora, encoding, epub, markdown, sentence, word};
+use crate::database::model::source::Source;
+use crate::database::model::source_file::{NewSourceFile, SourceFile};
+use crate::database::sql_types::{KanjiChar, SqlPath};
+use crate::manager::ManagerExt;
+use anyhow::Result;
+use encoding_rs::Encoding;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs::{self, Metadata};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tauri::AppHandle;
+
+/// Brings the kanji and snippet index of each source up to date.
+///
+/// Only files whose size or modification time changed since the last scan are read again.
+/// Files that no longer exist are removed from the index, while files that cannot be
+/// read or decoded are indexed without kanji and with a warning.
+pub fn blocking_scan(app: &AppHandle, sources: &[Source], task: &Task) -> Result<()> {
+  task.update(|progress| {
+    progress.sources_done = 0;
+    progress.sources_total = sources.len() as u64;
+  });
+
+  for source in sources {
+    blocking_scan_source(app, source, task)?;
+    task.update(|progress| progress.sources_done = progress.sources_done.saturating_add(1));
+  }
+
+  Ok(())
+}
+
+pub fn blocking_scan_source(app: &AppHandle, source: &Source, task: &Task) -> Result<ScanSummary> {
+  let mut summary = ScanSummary::default();
+  let db = app.database();
+  let encoding = source
+    .encoding
+    .as_deref()
+    .map(encoding::from_label)
+    .transpose()?;
+
+  // A broken dictionary should not prevent scanning, which everything else depends on.
+  let tokenizer = tokenizer::load(app)
+    .map(Some)
+    .unwrap_or_else(|err| {
+      log::warn!("failed to load the tokenizer dictionary: {err:?}");
+      None
+    });
+
+  let identity = tokenizer
+    .as_deref()
+    .map(|tokenizer| tokenizer.identity().to_owned());
+
+  let mut indexed: HashMap<SqlPath, SourceFile> = db
+    .get_source_files(source.id)?
+    .into_iter()
+    .map(|file| (file.path.clone(), file))
+    .collect();
+
+  for path in walk_source(source)? {
+    task.check()?;
+    task.update(|progress| progress.files_scanned = progress.files_scanned.saturating_add(1));
+
+    // Files may be gone since the walk, such as the temporary ones of editors, in which case
+    // they are left out like any other removed file.
+    let metadata = match fs::metadata(&path) {
+      Ok(metadata) => metadata,
+      Err(err) => {
+        log::warn!("skipped {}: {err}", path.display());
+        continue;
+      }
+    };
+
+    let size = i64::try_from(metadata.len())?;
+    let modified_at = modified_at(&metadata)?;
+
+    let path = SqlPath::from(path);
+    if let Some(file) = indexed.remove(&path)
+      && file.size == size
+      && file.modified_at == modified_at
+      && file.tokenizer == identity
+    {
+      continue;
+    }
+
+    let tokenizer = tokenizer.as_deref();
+    let (scanned, warning) = match read(source, &path, encoding, tokenizer) {
+      Ok(scanned) => (scanned, None),
+      Err(err) => (ScannedFile::default(), Some(err.to_string())),
+    };
+
+    NewSourceFile::builder(source.id, path)
+      .size(size)
+      .modified_at(modified_at)
+      .maybe_warning(warning)
+      .maybe_tokenizer(identity.clone())
+      .build()
+      .create(app, &scanned)?;
+
+    summary.updated_files = summary.updated_files.saturating_add(1);
+  }
+
+  let stale = indexed
+    .into_values()
+    .map(|file| file.id)
+    .collect_vec();
+
+  if !stale.is_empty() {
+    summary.removed_files = db.remove_source_files(&stale)?;
+  }
+
+  if !summary.is_empty() || !db.has_kanji_snapshot(source.id)? {
+    db.create_kanji_snapshot(source.id)?;
+  }
+
+  Ok(summary)
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ScanSummary {
+  pub updated_files: usize,
+  pub removed_files: usize,
+}
+
+impl ScanSummary {
+  pub fn is_empty(&self) -> bool {
+    self.updated_files == 0 && self.removed_files == 0
+  }
+}
+
+#[derive(Default)]
+pub struct ScannedFile<'a> {
+  pub kanjis: HashMap<KanjiChar, u32>,
+  pub words: HashMap<String, u32>,
+  pub snippets: Vec<ScannedSnippet>,
+  tokenizer: Option<&'a Tokenizer>,
+}
+
+impl<'a> ScannedFile<'a> {
+  fn new(tokenizer: Option<&'a Tokenizer>) -> Self {
+    Self { tokenizer, ..Self::default() }
+  }
+
+  fn from_lines(lines: &[(usize, String)], tokenizer: Option<&'a Tokenizer>) -> Self {
+    let mut file = Self::new(tokenizer);
+    for (line, content) in lines {
+      let line = line.saturating_add(1);
+      file.push(SnippetLocation::Line { line }, content);
+    }
+
+    file
+  }
+
+  fn from_chapters(chapters: &[Vec<String>], tokenizer: Option<&'a Tokenizer>) -> Self {
+    let mut file = Self::new(tokenizer);
+    for (chapter, paragraphs) in chapters.iter().enumerate() {
+      for (position, content) in paragraphs.iter().enumerate() {
+        let location = SnippetLocation::Chapter {
+          chapter: chapter.saturating_add(1),
+          position: position.saturating_add(1),
+        };
+
+        file.push(location, content);
+      }
+    }
+
+    file
+  }
+
+  fn from_cues(cues: &[Cue], tokenizer: Option<&'a Tokenizer>) -> Self {
+    let mut file = Self::new(tokenizer);
+    for cue in cues {
+      let location = SnippetLocation::Cue {
+        line: cue.line,
+        start: cue.start,
+        end: cue.end,
+      };
+
+      file.push(location, &cue.text);
+    }
+
+    file
+  }
+
+  fn push(&mut self, location: SnippetLocation, content: &str) {
+    let (content, furigana) = aozora::parse_line(content.trim());
+    let mut distinct = Vec::new();
+
+    for kanji in content
+      .chars()
+      .filter_map(KanjiChar::from_char)
+    {
+      let seen = self.kanjis.entry(kanji).or_insert(0u32);
+      *seen = seen.saturating_add(1);
+
+      if !distinct.contains(&kanji) {
+        distinct.push(kanji);
+      }
+    }
+
+    // With a tokenizer, the words are the dictionary forms of those it finds,
+    // instead of the runs of kanji guessed from the characters alone.
+    let tokens = self
+      .tokenizer
+      .map(|tokenizer| tokenizer.tokenize(&content))
+      .unwrap_or_default();
+
+    let words = if self.tokenizer.is_some() {
+      word::from_tokens(&tokens)
+    } else {
+      word::extract(&content)
+    };
+
+    for word in words {
+      let seen = self.words.entry(word).or_insert(0u32);
+      *seen = seen.saturating_add(1);
+    }
+
+    if !distinct.is_empty() {
+      self.snippets.push(ScannedSnippet {
+        location,
+        content,
+        furigana,
+        tokens,
+        kanjis: distinct.iter().join(" "),
+      });
+    }
+  }
+}
+
+pub struct ScannedSnippet {
+  pub location: SnippetLocation,
+  pub content: String,
+  pub furigana: Vec<Ruby>,
+  pub tokens: Vec<Token>,
+
+  /// Distinct kanji of the snippet, separated by spaces.
+  pub kanjis: String,
+}
+
+fn read<'a>(
+  source: &Source,
+  path: &Path,
+  encoding: Option<&'static Encoding>,
+  tokenizer: Option<&'a Tokenizer>,
+) -> Result<ScannedFile<'a>> {
+  let extension = path
+    .extension()
+    .and_then(OsStr::to_str)
+    .unwrap_or_default()
+    .to_ascii_lowercase();
+
+  if extension == "epub" {
+    return Ok(ScannedFile::from_chapters(&epub::read(path)?, tokenizer));
+  }
+
+  let text = encoding::decode(&fs::read(path)?, encoding)?;
+  if subtitle::EXTENSIONS.contains(&extension.as_str()) {
+    Ok(ScannedFile::from_cues(
+      &subtitle::parse(&extension, &text),
+      tokenizer,
+    ))
+  } else if matches!(extension.as_str(), "md" | "markdown") {
+    let lines = markdown::body_lines(&text, source.include_headings);
+    Ok(ScannedFile::from_lines(&lines, tokenizer))
+  } else {
+    let lines = sentence::join_wrapped(&aozora::body_lines(&text));
+    Ok(ScannedFile::from_lines(&lines, tokenizer))
+  }
+}
+
+fn modified_at(metadata: &Metadata) -> Result<i64> {
+  let nanos = metadata
+    .modified()?
+    .duration_since(UNIX_EPOCH)?
+    .as_nanos();
+
+  Ok(i64::try_from(nanos)?)
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+const TERMINATORS: &[char] = &['。', '！', '？', '!', '?'];
+const OPENING_QUOTES: &[char] = &['「', '『', '（', '(', '【', '〈', '《', '“'];
+const CLOSING_QUOTES: &[char] = &['」', '』', '）', ')', '】', '〉', '》', '”'];
+
+/// Lines shorter than this are never considered to be wrapped.
+const MIN_WRAP_WIDTH: usize = 20;
+
+/// Splits the text into sentences, returning their ranges in characters.
+///
+/// A sentence ends after a run of terminators and the closing quotes following them.
+/// A closing quote also ends one if another quote or some whitespace comes next, so that
+/// lines of dialogue written one after the other are kept apart.
+pub fn split(text: &str) -> Vec<Range<usize>> {
+  let chars = text.chars().collect::<Vec<_>>();
+  let mut sentences = Vec::new();
+  let mut start = 0;
+  let mut index = 0;
+
+  while let Some(&c) = chars.get(index) {
+    index += 1;
+
+    let ends = if TERMINATORS.contains(&c) {
+      while chars
+        .get(index)
+        .is_some_and(|it| TERMINATORS.contains(it) || CLOSING_QUOTES.contains(it))
+      {
+        index += 1;
+      }
+
+      true
+    } else {
+      CLOSING_QUOTES.contains(&c)
+        && chars
+          .get(index)
+          .is_some_and(|it| OPENING_QUOTES.contains(it) || it.is_whitespace())
+    };
+
+    if ends {
+      push_trimmed(&chars, start..index, &mut sentences);
+      start = index;
+    }
+  }
+
+  push_trimmed(&chars, start..chars.len(), &mut sentences);
+  sentences
+}
+
+fn push_trimmed(chars: &[char], mut range: Range<usize>, sentences: &mut Vec<Range<usize>>) {
+  while range.start < range.end && chars[range.start].is_whitespace() {
+    range.start += 1;
+  }
+
+  while range.end > range.start && chars[range.end - 1].is_whitespace() {
+    range.end -= 1;
+  }
+
+  if !range.is_empty() {
+    sentences.push(range);
+  }
+}
+
+/// Appends a line to the end of a paragraph, only separating them with a space
+/// if both sides of the break are Latin.
+pub fn join(paragraph: &mut String, line: &str) {
+  if paragraph
+    .chars()
+    .next_back()
+    .is_some_and(|c| c.is_ascii_alphanumeric() || c.is_ascii_punctuation())
+    && line
+      .chars()
+      .next()
+      .is_some_and(|c| c.is_ascii_alphanumeric())
+  {
+    paragraph.push(' ');
+  }
+
+  paragraph.push_str(line);
+}
+
+/// Joins the lines of text that was hard-wrapped at a fixed width back into paragraphs,
+/// keeping the index of the first line of each.
+///
+/// The width is the most common line length, if enough lines share it. A line as long as that
+/// continues on the next one, unless the latter is indented or opens a quote, which is how
+/// Japanese text marks the start of a paragraph.
+pub fn join_wrapped<S: AsRef<str>>(lines: &[(usize, S)]) -> Vec<(usize, String)> {
+  let width = wrap_width(lines);
+  let mut paragraphs: Vec<(usize, String)> = Vec::with_capacity(lines.len());
+  let mut previous: Option<(usize, usize)> = None;
+
+  for (index, line) in lines {
+    let line = line.as_ref();
+    let len = line.trim_end().chars().count();
+    let continues = width.is_some_and(|width| {
+      previous.is_some_and(|(previous_index, previous_len)| {
+        previous_index.saturating_add(1) == *index && previous_len.saturating_add(1) >= width
+      })
+    }) && line
+      .chars()
+      .next()
+      .is_some_and(|c| !c.is_whitespace() && !OPENING_QUOTES.contains(&c));
+
+    match paragraphs.last_mut() {
+      Some((_, paragraph)) if continues => join(paragraph, line.trim()),
+      _ => paragraphs.push((*index, line.trim().to_owned())),
+    }
+
+    previous = (len > 0).then_some((*index, len));
+  }
+
+  paragraphs
+}
+
+fn wrap_width<S: AsRef<str>>(lines: &[(usize, S)]) -> Option<usize> {
+  let mut counts: HashMap<usize, usize> = HashMap::new();
+  let mut total = 0usize;
+
+  for (_, line) in lines {
+    let len = line.as_ref().trim_end().chars().count();
+    if len > 0 {
+      total += 1;
+      *counts.entry(len).or_default() += 1;
+    }
+  }
+
+  counts
+    .into_iter()
+    .filter(|(len, count)| *len >= MIN_WRAP_WIDTH && count.saturating_mul(4) >= total)
+    .max_by_key(|(len, count)| (*count, *len))
+    .map(|(len, _)| len)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn sentences(text: &str) -> Vec<String> {
+    let chars = text.chars().collect::<Vec<_>>();
+    split(text)
+      .into_iter()
+      .map(|range| chars[range].iter().collect())
+      .collect()
+  }
+
+  #[test]
+  fn splits_after_terminators() {
+    assert_eq!(
+      sentences("今日は晴れ。明日は雨！？本当に"),
+      ["今日は晴れ。", "明日は雨！？", "本当に"]
+    );
+  }
+
+  #[test]
+  fn keeps_closing_quotes_with_their_sentence() {
+    assert_eq!(
+      sentences("「行こう。」そうだね。"),
+      ["「行こう。」", "そうだね。"]
+    );
+  }
+
+  #[test]
+  fn splits_between_quotes() {
+    assert_eq!(sentences("「はい」「いいえ」"), ["「はい」", "「いいえ」"]);
+    assert_eq!(sentences("「はい」と言った"), ["「はい」と言った"]);
+  }
+
+  #[test]
+  fn counts_ranges_in_characters_without_whitespace() {
+    assert_eq!(split("  行こう。 そうだね "), [2..6, 7..11]);
+    assert!(split(" \n ").is_empty());
+  }
+
+  #[test]
+  fn joins_latin_with_a_space() {
+    let mut paragraph = String::from("Hello,");
+    join(&mut paragraph, "world");
+    join(&mut paragraph, "日本");
+    join(&mut paragraph, "語");
+    assert_eq!(paragraph, "Hello, world日本語");
+  }
+
+  #[test]
+  fn joins_wrapped_lines() {
+    let full = "あ".repeat(20);
+    let lines = [
+      (0, full.as_str()),
+      (1, full.as_str()),
+      (2, "終わり。"),
+      (3, "　次の段落。"),
+      (4, full.as_str()),
+      (5, "「続かない」"),
+      (7, "離れた行"),
+    ];
+
+    let paragraphs = join_wrapped(&lines);
+    assert_eq!(
+      paragraphs,
+      [
+        (0, format!("{full}{full}終わり。")),
+        (3, "次の段落。".to_owned()),
+        (4, full.clone()),
+        (5, "「続かない」".to_owned()),
+        (7, "離れた行".to_owned()),
+      ]
+    );
+  }
+
+  #[test]
+  fn leaves_short_lines_alone() {
+    let lines = [(0, "短い行"), (1, "短い行")];
+    assert_eq!(join_wrapped(&lines).len(), 2);
+  }
+}
@@ -10,22 +10,33 @@ use std::sync::atomic::Ordering::Relaxed;
 
 #[cfg(desktop)]
 use {
-  crate::core::kanji::is_kanji,
-  crate::core::source::walk_source,
+  crate::core::kanji::{counts_as_kanji, is_kanji},
+  crate::core::scan::blocking_scan,
+  crate::core::task::Task,
+  crate::core::{sentence, variant},
   crate::database::model::bookmark::NewBookmark,
   crate::database::model::source::Source,
+  crate::database::model::source_snippet::SourceSnippetMatch,
+  crate::database::sql_types::SqlPath,
   crate::manager::ManagerExt,
+  anyhow::bail,
   itertools::Itertools,
-  memchr::memmem::Finder,
   rand::seq::{IndexedRandom, SliceRandom},
-  std::fs::File,
-  std::io::BufRead,
+  std::collections::HashMap,
+  std::ops::Range,
   tauri::AppHandle,
   tauri::async_runtime::spawn_blocking,
 };
 
 static ID: AtomicU64 = AtomicU64::new(0);
 
+#[cfg(desktop)]
+const ELLIPSIS: char = '…';
+
+/// Spellings of a word searched at most, since each kanji with variants multiplies them.
+#[cfg(desktop)]
+const MAX_SEARCH_FORMS: usize = 64;
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Snippet {
@@ -33,6 +44,21 @@ pub struct Snippet {
   content: Arc<str>,
   source: SnippetSource,
 
+  #[serde(default)]
+  furigana: Vec<Ruby>,
+
+  /// Words of the snippet, as found by the tokenizer when it was indexed.
+  #[serde(default)]
+  tokens: Vec<Token>,
+
+  /// Sentences coming right before the snippet.
+  #[serde(default)]
+  context_before: Option<Arc<str>>,
+
+  /// Sentences coming right after the snippet.
+  #[serde(default)]
+  context_after: Option<Arc<str>>,
+
   #[serde(skip_deserializing)]
   bookmark: Option<BookmarkId>,
 }
@@ -50,10 +76,67 @@ impl Snippet {
     self.content.contains(*kanji)
   }
 
+  /// Removes the readings of every ruby and token whose base contains any of the kanji.
+  pub fn hide_readings_of(&mut self, kanjis: &[KanjiChar]) {
+    let chars = self.content.chars().collect::<Vec<_>>();
+    let contains = |start: usize, len: usize| {
+      chars
+        .iter()
+        .skip(start)
+        .take(len)
+        .any(|c| kanjis.iter().any(|kanji| *c == **kanji))
+    };
+
+    self
+      .furigana
+      .retain(|ruby| !contains(ruby.start, ruby.len));
+    self
+      .tokens
+      .retain(|token| !contains(token.start, token.len));
+  }
+
   #[cfg(desktop)]
   pub fn create_bookmark(&self, app: &AppHandle) -> Result<BookmarkId> {
     NewBookmark::from(self).create(app)
   }
+
+  /// Gives a snippet read back from the database a new id and its current bookmark,
+  /// since neither is kept when it is stored.
+  #[cfg(desktop)]
+  pub fn refresh(&mut self, app: &AppHandle) -> Result<()> {
+    self.id = SnippetId::new();
+    self.bookmark = app
+      .database()
+      .get_bookmark_id(&self.content)?;
+    Ok(())
+  }
+}
+
+/// Reading of part of the content, whose offsets are counted in characters.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ruby {
+  pub start: usize,
+  pub len: usize,
+  pub reading: String,
+}
+
+/// Word found by the tokenizer, whose offsets are counted in characters.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Token {
+  pub start: usize,
+  pub len: usize,
+  pub surface: String,
+
+  /// Part of speech, such as `名詞-一般`.
+  pub pos: String,
+
+  /// Dictionary form of the word, which is missing for unknown words.
+  pub lemma: Option<String>,
+
+  /// Reading of the word in katakana, which is missing for unknown words.
+  pub reading: Option<String>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -100,7 +183,9 @@ pub struct SnippetSource {
   name: Arc<str>,
   path: Arc<StdPath>,
   weight: SourceWeight,
-  line: usize,
+
+  #[serde(flatten)]
+  location: SnippetLocation,
 }
 
 impl SnippetSource {
@@ -109,19 +194,39 @@ impl SnippetSource {
   }
 }
 
+/// Where the snippet was found within its file.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum SnippetLocation {
+  Line {
+    line: usize,
+  },
+  Chapter {
+    chapter: usize,
+    position: usize,
+  },
+
+  /// A subtitle cue, with its timestamps in milliseconds.
+  Cue {
+    line: usize,
+    start: u64,
+    end: u64,
+  },
+}
+
 #[cfg(desktop)]
 pub async fn search(
   app: AppHandle,
-  kanji: KanjiChar,
+  word: String,
   source: Option<SourceId>,
 ) -> Result<Vec<Snippet>> {
-  spawn_blocking(move || blocking_search(&app, kanji, source)).await?
+  spawn_blocking(move || blocking_search(&app, &word, source)).await?
 }
 
 #[cfg(desktop)]
 pub fn blocking_search(
   app: &AppHandle,
-  kanji: KanjiChar,
+  word: &str,
   source: Option<SourceId>,
 ) -> Result<Vec<Snippet>> {
   let sources = if let Some(id) = source {
@@ -130,67 +235,103 @@ pub fn blocking_search(
     app.database().get_enabled_sources()?
   };
 
-  blocking_search_with_options(app, kanji)
+  blocking_scan(app, &sources, &Task::new())?;
+  blocking_search_with_options(app, word)
     .sources(&sources)
     .limit(settings::snippet_limit(app))
+    .max_len(settings::snippet_max_len(app))
+    .context(settings::snippet_context(app))
     .shuffle(settings::shuffle_snippets(app))
     .call()
 }
 
+/// Searches the indexed paragraphs containing the word, which may be a single kanji,
+/// splitting them into sentences. If enabled in the settings, paragraphs spelling the word
+/// with variants of its kanji are found too.
+///
+/// Each sentence with the word becomes a snippet, shortened around its first occurrence if
+/// it is longer than `max_len`, along with up to `context` sentences before and after it.
 #[cfg(desktop)]
 #[bon::builder]
 pub fn blocking_search_with_options(
   #[builder(start_fn)] app: &AppHandle,
-  #[builder(start_fn)] kanji: KanjiChar,
+  #[builder(start_fn)] word: &str,
   #[builder(default)] sources: &[Source],
   #[builder(default = settings::DEFAULT_SNIPPET_LIMIT)] limit: usize,
+  #[builder(default = settings::DEFAULT_SNIPPET_MAX_LEN)] max_len: usize,
+  #[builder(default = settings::DEFAULT_SNIPPET_CONTEXT)] context: usize,
   #[builder(default = settings::DEFAULT_SHUFFLE_SNIPPETS)] shuffle: bool,
 ) -> Result<Vec<Snippet>> {
   let db = app.database();
   let mut snippets = Vec::new();
 
-  let mut buf = [0u8; 4];
-  let finder = Finder::new(kanji.encode_utf8(&mut buf));
-
   let min_len = settings::snippet_min_len(app);
   let forbidden_words = settings::forbidden_words(app);
   let ignore_source_weight = settings::ignore_source_weight(app);
+  let iteration_marks = settings::iteration_marks(app);
+  let forms = search_forms(app, word);
+  if !forms
+    .iter()
+    .any(|form| form.chars().any(is_kanji))
+  {
+    bail!("{word} has no kanji");
+  }
 
   for source in sources {
     let source_id = source.id;
     let name = Arc::from(source.name.as_str());
     let weight = source.weight;
-
-    for path in walk_source(source) {
-      let path = Arc::from(path);
-      let file = File::open_buffered(&path)?;
-
-      for (line, text) in file.lines().enumerate() {
-        let Ok(text) = text else { continue };
-
-        let text = text.trim();
-        if !should_skip(text, min_len, &forbidden_words) {
-          let bytes = text.as_bytes();
-          if finder.find(bytes).is_some() {
-            let name = Arc::clone(&name);
-            let path = Arc::clone(&path);
-            let line = line.saturating_add(1);
-            let source = SnippetSource {
-              id: source_id,
-              name,
-              path,
-              weight,
-              line,
-            };
-
-            snippets.push(Snippet {
-              id: SnippetId::new(),
-              content: Arc::from(text),
-              source,
-              bookmark: db.get_bookmark_id(text)?,
-            });
-          }
+    let mut paths: HashMap<SqlPath, Arc<StdPath>> = HashMap::new();
+
+    for found in db.search_source_snippets(source_id, &forms)? {
+      let location = SnippetLocation::new(&found)?;
+      let furigana: Vec<Ruby> = found
+        .furigana
+        .as_deref()
+        .map(serde_json::from_str)
+        .transpose()?
+        .unwrap_or_default();
+
+      let tokens: Vec<Token> = found
+        .tokens
+        .as_deref()
+        .map(serde_json::from_str)
+        .transpose()?
+        .unwrap_or_default();
+
+      let path = paths
+        .entry(found.path)
+        .or_insert_with_key(|path| Arc::from(&**path));
+
+      let source = SnippetSource {
+        id: source_id,
+        name: Arc::clone(&name),
+        path: Arc::clone(path),
+        weight,
+        location,
+      };
+
+      for sentence in split_sentences(&found.content, &furigana, &tokens, &forms, max_len, context)
+      {
+        if should_skip(
+          &sentence.content,
+          min_len,
+          iteration_marks,
+          &forbidden_words,
+        ) {
+          continue;
         }
+
+        snippets.push(Snippet {
+          id: SnippetId::new(),
+          content: Arc::from(sentence.content),
+          source: source.clone(),
+          furigana: sentence.furigana,
+          tokens: sentence.tokens,
+          context_before: sentence.context_before.map(Arc::from),
+          context_after: sentence.context_after.map(Arc::from),
+          bookmark: None,
+        });
       }
     }
   }
@@ -221,6 +362,10 @@ pub fn blocking_search_with_options(
 
   snippets.retain(|snippet| chosen.contains(&snippet.id));
 
+  for snippet in &mut snippets {
+    snippet.bookmark = db.get_bookmark_id(&snippet.content)?;
+  }
+
   if shuffle {
     snippets.shuffle(&mut rng);
   }
@@ -228,8 +373,253 @@ pub fn blocking_search_with_options(
   Ok(snippets)
 }
 
+/// Returns the forms of the word to look for, which are all the spellings made of the
+/// variants of each of its kanji only if normalization is enabled in the settings.
 #[cfg(desktop)]
-fn should_skip(text: &str, min_len: usize, forbidden_words: &[String]) -> bool {
+fn search_forms(app: &AppHandle, word: &str) -> Vec<String> {
+  if !settings::normalize_variants(app) {
+    return vec![word.to_owned()];
+  }
+
+  let mut forms = vec![String::new()];
+  for c in word.chars() {
+    let variants = KanjiChar::from_char(c).map_or_else(
+      || vec![c],
+      |kanji| {
+        variant::forms(kanji)
+          .into_iter()
+          .map(|form| *form)
+          .collect()
+      },
+    );
+
+    forms = forms
+      .iter()
+      .cartesian_product(&variants)
+      .take(MAX_SEARCH_FORMS)
+      .map(|(form, c)| {
+        let mut form = form.clone();
+        form.push(*c);
+        form
+      })
+      .collect();
+  }
+
+  forms
+}
+
+#[cfg(desktop)]
+impl SnippetLocation {
+  fn new(found: &SourceSnippetMatch) -> Result<Self> {
+    let line = usize::try_from(found.line)?;
+    let location = if let Some(chapter) = found.chapter {
+      Self::Chapter {
+        chapter: usize::try_from(chapter)?,
+        position: line,
+      }
+    } else if let (Some(start), Some(end)) = (found.cue_start, found.cue_end) {
+      Self::Cue {
+        line,
+        start: u64::try_from(start)?,
+        end: u64::try_from(end)?,
+      }
+    } else {
+      Self::Line { line }
+    };
+
+    Ok(location)
+  }
+}
+
+#[cfg(desktop)]
+struct Sentence {
+  content: String,
+  furigana: Vec<Ruby>,
+  tokens: Vec<Token>,
+  context_before: Option<String>,
+  context_after: Option<String>,
+}
+
+#[cfg(desktop)]
+fn split_sentences(
+  content: &str,
+  furigana: &[Ruby],
+  tokens: &[Token],
+  forms: &[String],
+  max_len: usize,
+  context: usize,
+) -> Vec<Sentence> {
+  let chars = content.chars().collect_vec();
+  let ranges = sentence::split(content);
+  let text = |range: Range<usize>| {
+    chars[range]
+      .iter()
+      .collect::<String>()
+      .trim()
+      .to_owned()
+  };
+
+  let mut sentences = Vec::new();
+  for (position, range) in ranges.iter().enumerate() {
+    let Some(at) = find_first(&chars, range, tokens, forms) else {
+      continue;
+    };
+
+    // The window is trimmed before the offsets of the readings are made relative to it,
+    // so that they still point at their base once leading whitespace was cut.
+    let window = trim(&chars, shorten(range, at, max_len));
+    let mut content = chars[window.clone()]
+      .iter()
+      .collect::<String>();
+    let mut furigana = furigana
+      .iter()
+      .filter(|ruby| ruby.start >= window.start && ruby.start + ruby.len <= window.end)
+      .map(|ruby| {
+        Ruby {
+          start: ruby.start - window.start,
+          ..ruby.clone()
+        }
+      })
+      .collect_vec();
+
+    let mut tokens = tokens
+      .iter()
+      .filter(|token| token.start >= window.start && token.start + token.len <= window.end)
+      .map(|token| {
+        Token {
+          start: token.start - window.start,
+          ..token.clone()
+        }
+      })
+      .collect_vec();
+
+    if window.start > range.start {
+      content.insert(0, ELLIPSIS);
+      furigana
+        .iter_mut()
+        .for_each(|ruby| ruby.start += 1);
+      tokens
+        .iter_mut()
+        .for_each(|token| token.start += 1);
+    }
+
+    if window.end < range.end {
+      content.push(ELLIPSIS);
+    }
+
+    let context_before = (context > 0 && position > 0).then(|| {
+      let start = ranges[position.saturating_sub(context)].start;
+      let before = text(start..range.start);
+      let len = before.chars().count();
+      if max_len > 0 && len > max_len {
+        let mut shortened = String::from(ELLIPSIS);
+        shortened.extend(before.chars().skip(len - max_len));
+        shortened
+      } else {
+        before
+      }
+    });
+
+    let context_after = (context > 0 && position + 1 < ranges.len()).then(|| {
+      let last = (position + context).min(ranges.len() - 1);
+      let after = text(range.end..ranges[last].end);
+      if max_len > 0 && after.chars().count() > max_len {
+        let mut shortened = after
+          .chars()
+          .take(max_len)
+          .collect::<String>();
+        shortened.push(ELLIPSIS);
+        shortened
+      } else {
+        after
+      }
+    });
+
+    sentences.push(Sentence {
+      content,
+      furigana,
+      tokens,
+      context_before,
+      context_after,
+    });
+  }
+
+  sentences
+}
+
+/// Returns the position in characters of the first occurrence of any of the forms within
+/// the range. Tokens whose dictionary form is one of them count too, so that a word stored
+/// as `食べる` is found where the text has `食べた`.
+#[cfg(desktop)]
+fn find_first(
+  chars: &[char],
+  range: &Range<usize>,
+  tokens: &[Token],
+  forms: &[String],
+) -> Option<usize> {
+  let forms = forms
+    .iter()
+    .map(|form| form.chars().collect_vec())
+    .filter(|form| !form.is_empty())
+    .collect_vec();
+
+  let literal = range.clone().find(|start| {
+    forms
+      .iter()
+      .any(|form| chars[*start..range.end].starts_with(form))
+  });
+
+  let lemma = tokens
+    .iter()
+    .filter(|token| range.contains(&token.start))
+    .find(|token| {
+      token.lemma.as_ref().is_some_and(|lemma| {
+        forms
+          .iter()
+          .any(|form| lemma.chars().eq(form.iter().copied()))
+      })
+    })
+    .map(|token| token.start);
+
+  literal.into_iter().chain(lemma).min()
+}
+
+/// Returns a window of at most `max_len` characters of the range, centered around `at`
+/// as far as possible. A `max_len` of zero means there is no limit.
+#[cfg(desktop)]
+fn shorten(range: &Range<usize>, at: usize, max_len: usize) -> Range<usize> {
+  if max_len == 0 || range.len() <= max_len {
+    return range.clone();
+  }
+
+  let start = at
+    .saturating_sub(max_len / 2)
+    .clamp(range.start, range.end - max_len);
+
+  start..start + max_len
+}
+
+/// Shrinks the range so that it neither starts nor ends with whitespace.
+#[cfg(desktop)]
+fn trim(chars: &[char], mut range: Range<usize>) -> Range<usize> {
+  while range.start < range.end && chars[range.start].is_whitespace() {
+    range.start += 1;
+  }
+
+  while range.end > range.start && chars[range.end - 1].is_whitespace() {
+    range.end -= 1;
+  }
+
+  range
+}
+
+#[cfg(desktop)]
+fn should_skip(
+  text: &str,
+  min_len: usize,
+  iteration_marks: bool,
+  forbidden_words: &[String],
+) -> bool {
   if text.is_empty()
     || text.starts_with('#')
     || text.starts_with('<')
@@ -242,7 +632,7 @@ fn should_skip(text: &str, min_len: usize, forbidden_words: &[String]) -> bool {
 
   let mut matches: usize = 0;
   for char in text.chars() {
-    if is_kanji(char) {
+    if counts_as_kanji(char, iteration_marks) {
       matches = matches.saturating_add(1);
     }
 
@@ -253,3 +643,78 @@ fn should_skip(text: &str, min_len: usize, forbidden_words: &[String]) -> bool {
 
   true
 }
+
+#[cfg(all(test, desktop))]
+mod tests {
+  use super::*;
+
+  fn token(start: usize, surface: &str, lemma: &str) -> Token {
+    Token {
+      start,
+      len: surface.chars().count(),
+      surface: surface.to_owned(),
+      pos: String::new(),
+      lemma: Some(lemma.to_owned()),
+      reading: None,
+    }
+  }
+
+  #[test]
+  fn shifts_readings_and_tokens_into_shortened_sentences() {
+    let content = "前の文。そして長い文の中で日本語を話す。後の文。";
+    let furigana = [
+      Ruby {
+        start: 0,
+        len: 1,
+        reading: "まえ".to_owned(),
+      },
+      Ruby {
+        start: 13,
+        len: 2,
+        reading: "にほん".to_owned(),
+      },
+    ];
+    let tokens = [token(13, "日本語", "日本語"), token(17, "話す", "話す")];
+    let forms = ["日本".to_owned()];
+
+    let sentences = split_sentences(content, &furigana, &tokens, &forms, 6, 1);
+    let [sentence] = sentences.as_slice() else {
+      panic!("expected a single sentence");
+    };
+
+    assert_eq!(sentence.content, "…の中で日本語…");
+    assert_eq!(sentence.context_before.as_deref(), Some("前の文。"));
+    assert_eq!(sentence.context_after.as_deref(), Some("後の文。"));
+
+    let [ruby] = sentence.furigana.as_slice() else {
+      panic!("expected a single reading");
+    };
+    assert_eq!((ruby.start, ruby.len), (4, 2));
+
+    let [token] = sentence.tokens.as_slice() else {
+      panic!("expected a single token");
+    };
+    assert_eq!((token.start, token.len), (4, 3));
+  }
+
+  #[test]
+  fn finds_sentences_by_dictionary_form() {
+    let content = "雨だ。昨日は寿司を食べた。";
+    let tokens = [token(9, "食べ", "食べる"), token(11, "た", "た")];
+    let forms = ["食べる".to_owned()];
+
+    let sentences = split_sentences(content, &[], &tokens, &forms, 0, 0);
+    let [sentence] = sentences.as_slice() else {
+      panic!("expected a single sentence");
+    };
+
+    assert_eq!(sentence.content, "昨日は寿司を食べた。");
+    assert_eq!(sentence.context_before, None);
+    let starts = sentence
+      .tokens
+      .iter()
+      .map(|token| token.start)
+      .collect_vec();
+    assert_eq!(starts, [6, 8]);
+  }
+}
@@ -1,36 +1,51 @@
 use crate::database::model::source::Source;
-use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use anyhow::Result;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use ignore::{DirEntry, WalkBuilder};
-use itertools::Itertools;
 use std::path::PathBuf;
-use std::sync::LazyLock;
 
-static GLOBSET: LazyLock<GlobSet> = LazyLock::new(|| {
-  GlobSetBuilder::new()
-    .add(glob("*.md"))
-    .add(glob("*.txt"))
-    .build()
-    .unwrap()
-});
+pub fn walk_source(source: &Source) -> Result<Vec<PathBuf>> {
+  let include = globset(&source.include)?;
+  let exclude = globset(&source.exclude)?;
+  let max_depth = source
+    .max_depth
+    .map(usize::try_from)
+    .transpose()?;
 
-pub fn walk_source(source: &Source) -> Vec<PathBuf> {
-  WalkBuilder::new(&*source.path)
+  let mut files = Vec::new();
+  let walker = WalkBuilder::new(&*source.path)
     .add_custom_ignore_filename(".kanjignore")
     .current_dir(&*source.path)
     .follow_links(false)
-    .standard_filters(false)
-    .max_depth(Some(1))
+    .standard_filters(source.respect_gitignore)
+    .require_git(false)
+    .max_depth(max_depth)
     .sort_by_file_name(Ord::cmp)
-    .build()
-    .flatten()
-    .map(DirEntry::into_path)
-    .filter(|path| path.is_file() && GLOBSET.is_match(path))
-    .collect_vec()
+    .build();
+
+  for path in walker.flatten().map(DirEntry::into_path) {
+    let Ok(relative) = path.strip_prefix(&*source.path) else {
+      continue;
+    };
+
+    if path.is_file() && include.is_match(relative) && !exclude.is_match(relative) {
+      files.push(path);
+    }
+  }
+
+  Ok(files)
 }
 
-fn glob(glob: &str) -> Glob {
-  GlobBuilder::new(glob)
-    .case_insensitive(true)
-    .build()
-    .unwrap()
+/// Builds a case-insensitive glob set, failing if any of the globs is invalid.
+pub fn globset(globs: &[String]) -> Result<GlobSet> {
+  let mut builder = GlobSetBuilder::new();
+  for glob in globs {
+    builder.add(
+      GlobBuilder::new(glob)
+        .case_insensitive(true)
+        .build()?,
+    );
+  }
+
+  Ok(builder.build()?)
 }
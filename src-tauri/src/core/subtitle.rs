@@ -0,0 +1,257 @@
+use itertools::Itertools;
+
+pub const EXTENSIONS: &[&str] = &["ass", "srt", "ssa", "vtt"];
+
+pub struct Cue {
+  /// Line of the file where the cue starts.
+  pub line: usize,
+
+  /// Start of the cue, in milliseconds.
+  pub start: u64,
+
+  /// End of the cue, in milliseconds.
+  pub end: u64,
+
+  pub text: String,
+}
+
+/// Parses the cues of a subtitle file, stripping their formatting.
+pub fn parse(extension: &str, text: &str) -> Vec<Cue> {
+  if extension.eq_ignore_ascii_case("ass") || extension.eq_ignore_ascii_case("ssa") {
+    parse_ass(text)
+  } else {
+    parse_srt(text)
+  }
+}
+
+/// Parses SRT and VTT files, whose cues are blocks starting with a timing line.
+fn parse_srt(text: &str) -> Vec<Cue> {
+  let mut cues = Vec::new();
+  let mut lines = text.lines().enumerate().peekable();
+
+  while let Some((_, line)) = lines.next() {
+    let Some((start, end)) = line.split_once("-->") else {
+      continue;
+    };
+
+    let end = end
+      .split_whitespace()
+      .next()
+      .unwrap_or_default();
+    let (Some(start), Some(end)) = (parse_timestamp(start), parse_timestamp(end)) else {
+      continue;
+    };
+
+    let Some(&(index, _)) = lines.peek() else {
+      break;
+    };
+
+    let text = lines
+      .peeking_take_while(|(_, line)| !line.trim().is_empty())
+      .map(|(_, line)| strip_tags(line))
+      .filter(|line| !line.is_empty())
+      .join(" ");
+
+    cues.push(Cue {
+      line: index.saturating_add(1),
+      start,
+      end,
+      text,
+    });
+  }
+
+  cues
+}
+
+/// Parses the `Dialogue` events of ASS and SSA files.
+fn parse_ass(text: &str) -> Vec<Cue> {
+  let mut cues = Vec::new();
+  let mut format = ["layer", "start", "end", "style", "name", "text"]
+    .map(String::from)
+    .to_vec();
+
+  for (index, line) in text.lines().enumerate() {
+    let line = line.trim();
+    if let Some(fields) = line.strip_prefix("Format:") {
+      format = fields
+        .split(',')
+        .map(|field| field.trim().to_ascii_lowercase())
+        .collect();
+      continue;
+    }
+
+    let Some(fields) = line.strip_prefix("Dialogue:") else {
+      continue;
+    };
+
+    let values = fields
+      .splitn(format.len(), ',')
+      .collect_vec();
+    let field = |name: &str| {
+      format
+        .iter()
+        .position(|it| *it == name)
+        .and_then(|position| values.get(position))
+        .map(|value| value.trim())
+    };
+
+    let (Some(start), Some(end), Some(text)) = (field("start"), field("end"), field("text")) else {
+      continue;
+    };
+
+    let (Some(start), Some(end)) = (parse_timestamp(start), parse_timestamp(end)) else {
+      continue;
+    };
+
+    let text = strip_overrides(text)
+      .replace("\\N", " ")
+      .replace("\\n", " ")
+      .replace("\\h", " ");
+
+    cues.push(Cue {
+      line: index.saturating_add(1),
+      start,
+      end,
+      text: text.trim().to_owned(),
+    });
+  }
+
+  cues
+}
+
+/// Parses timestamps such as `01:02:03,456`, `02:03.456` or `1:02:03.45`.
+fn parse_timestamp(timestamp: &str) -> Option<u64> {
+  let timestamp = timestamp.trim();
+  let (clock, fraction) = timestamp
+    .rsplit_once(['.', ','])
+    .unwrap_or((timestamp, "0"));
+
+  let mut seconds = 0u64;
+  for part in clock.split(':') {
+    seconds = seconds
+      .checked_mul(60)?
+      .checked_add(part.trim().parse().ok()?)?;
+  }
+
+  let fraction = fraction.get(..3).unwrap_or(fraction);
+  let millis = fraction
+    .parse::<u64>()
+    .ok()?
+    .checked_mul(match fraction.len() {
+      1 => 100,
+      2 => 10,
+      _ => 1,
+    })?;
+
+  seconds
+    .checked_mul(1000)?
+    .checked_add(millis)
+}
+
+/// Removes markup such as `<i>`, `<font>` or `<v Speaker>`, along with ruby readings.
+fn strip_tags(line: &str) -> String {
+  let line = strip_overrides(line);
+  let mut text = String::with_capacity(line.len());
+  let mut rest = line.as_str();
+
+  while let Some(start) = rest.find('<') {
+    text.push_str(&rest[..start]);
+    let Some(len) = rest[start..].find('>') else {
+      rest = "";
+      break;
+    };
+
+    let tag = &rest[start..=start + len];
+    rest = &rest[start + len + 1..];
+
+    if tag.eq_ignore_ascii_case("<rt>") {
+      rest = rest
+        .find("</rt>")
+        .map_or("", |end| &rest[end..]);
+    }
+  }
+
+  text.push_str(rest);
+  text.trim().to_owned()
+}
+
+/// Removes override blocks such as `{\an8}` or `{\pos(10,20)}`.
+fn strip_overrides(line: &str) -> String {
+  let mut text = String::with_capacity(line.len());
+  let mut depth = 0usize;
+
+  for c in line.chars() {
+    match c {
+      '{' => depth = depth.saturating_add(1),
+      '}' if depth > 0 => depth = depth.saturating_sub(1),
+      _ if depth == 0 => text.push(c),
+      _ => {}
+    }
+  }
+
+  text
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn cues(extension: &str, text: &str) -> Vec<(usize, u64, u64, String)> {
+    parse(extension, text)
+      .into_iter()
+      .map(|cue| (cue.line, cue.start, cue.end, cue.text))
+      .collect()
+  }
+
+  #[test]
+  fn parses_srt() {
+    let text = "\
+1
+00:00:01,000 --> 00:00:02,500
+<i>こんにちは</i>
+世界
+
+2
+00:00:03,000 --> 00:00:04,000 position:10%
+{\\an8}<ruby>漢字<rt>かんじ</rt></ruby>
+";
+
+    assert_eq!(
+      cues("srt", text),
+      [
+        (3, 1000, 2500, "こんにちは 世界".to_owned()),
+        (8, 3000, 4000, "漢字".to_owned()),
+      ]
+    );
+  }
+
+  #[test]
+  fn parses_vtt() {
+    let text = "WEBVTT\n\n00:01.500 --> 00:02.000\n<v Taro>はい\n";
+    assert_eq!(cues("vtt", text), [(4, 1500, 2000, "はい".to_owned())]);
+  }
+
+  #[test]
+  fn parses_ass() {
+    let text = "\
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.50,Default,,0,0,0,,{\\i1}今日は、\\Nいい天気
+Comment: 0,0:00:03.00,0:00:04.00,Default,,0,0,0,,メモ
+";
+
+    assert_eq!(
+      cues("ASS", text),
+      [(3, 1000, 2500, "今日は、 いい天気".to_owned())]
+    );
+  }
+
+  #[test]
+  fn parses_timestamps() {
+    assert_eq!(parse_timestamp("01:02:03,456"), Some(3_723_456));
+    assert_eq!(parse_timestamp("02:03.456"), Some(123_456));
+    assert_eq!(parse_timestamp("1:02:03.45"), Some(3_723_450));
+    assert_eq!(parse_timestamp("00:00:01.2345"), Some(1234));
+    assert_eq!(parse_timestamp("soon"), None);
+  }
+}
@@ -0,0 +1,129 @@
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::nonpoison::Mutex;
+use uuid::Uuid;
+
+type OnProgress = Box<dyn Fn(&TaskProgress) + Send + Sync>;
+
+/// Handle to a long-running operation, used to report its progress and to cancel it.
+///
+/// Cloning is cheap and every clone refers to the same task.
+#[derive(Clone, Default)]
+pub struct Task(Arc<TaskInner>);
+
+#[derive(Default)]
+struct TaskInner {
+  cancelled: AtomicBool,
+  progress: Mutex<TaskProgress>,
+  on_progress: Option<OnProgress>,
+}
+
+impl Task {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_progress<F>(on_progress: F) -> Self
+  where
+    F: Fn(&TaskProgress) + Send + Sync + 'static,
+  {
+    Self(Arc::new(TaskInner {
+      on_progress: Some(Box::new(on_progress)),
+      ..TaskInner::default()
+    }))
+  }
+
+  pub fn cancel(&self) {
+    self.0.cancelled.store(true, Relaxed);
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.0.cancelled.load(Relaxed)
+  }
+
+  /// Returns an error if the task was cancelled.
+  pub fn check(&self) -> Result<()> {
+    if self.is_cancelled() {
+      bail!("Task was cancelled");
+    }
+
+    Ok(())
+  }
+
+  pub fn update(&self, f: impl FnOnce(&mut TaskProgress)) {
+    let mut progress = self.0.progress.lock();
+    f(&mut progress);
+
+    if let Some(on_progress) = &self.0.on_progress {
+      on_progress(&progress);
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskProgress {
+  pub files_scanned: u64,
+  pub sources_done: u64,
+  pub sources_total: u64,
+  pub questions_built: u64,
+  pub questions_total: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct TaskId(Uuid);
+
+type TaskMap = Arc<Mutex<HashMap<TaskId, Task>>>;
+
+/// Tasks that can be cancelled by their id.
+#[derive(Default)]
+pub struct Tasks(TaskMap);
+
+impl Tasks {
+  /// Registers the task under the given id, if any.
+  ///
+  /// The task is cancelled and unregistered once the returned handle is dropped,
+  /// so work is abandoned as soon as nobody is waiting for it anymore.
+  pub fn register(&self, id: Option<TaskId>, task: Task) -> TaskHandle {
+    if let Some(id) = id {
+      self.0.lock().insert(id, task.clone());
+    }
+
+    TaskHandle { id, task, tasks: Arc::clone(&self.0) }
+  }
+
+  /// Cancels the task with the given id, returning whether it was found.
+  pub fn cancel(&self, id: TaskId) -> bool {
+    self
+      .0
+      .lock()
+      .remove(&id)
+      .inspect(Task::cancel)
+      .is_some()
+  }
+}
+
+pub struct TaskHandle {
+  id: Option<TaskId>,
+  task: Task,
+  tasks: TaskMap,
+}
+
+impl TaskHandle {
+  pub fn task(&self) -> Task {
+    self.task.clone()
+  }
+}
+
+impl Drop for TaskHandle {
+  fn drop(&mut self) {
+    self.task.cancel();
+    if let Some(id) = self.id {
+      self.tasks.lock().remove(&id);
+    }
+  }
+}
@@ -0,0 +1,441 @@
+use crate::core::csv::split_fields;
+use crate::core::encoding;
+use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+
+/// Files of the small dictionary bundled with the app, which is used until another is set.
+pub const BUNDLED: [(&str, &str); 9] = [
+  (
+    "char.def",
+    include_str!("../../../data/dictionary/char.def"),
+  ),
+  (
+    "matrix.def",
+    include_str!("../../../data/dictionary/matrix.def"),
+  ),
+  ("unk.def", include_str!("../../../data/dictionary/unk.def")),
+  (
+    "adjective.csv",
+    include_str!("../../../data/dictionary/adjective.csv"),
+  ),
+  (
+    "auxiliary.csv",
+    include_str!("../../../data/dictionary/auxiliary.csv"),
+  ),
+  (
+    "noun.csv",
+    include_str!("../../../data/dictionary/noun.csv"),
+  ),
+  (
+    "other.csv",
+    include_str!("../../../data/dictionary/other.csv"),
+  ),
+  (
+    "particle.csv",
+    include_str!("../../../data/dictionary/particle.csv"),
+  ),
+  (
+    "verb.csv",
+    include_str!("../../../data/dictionary/verb.csv"),
+  ),
+];
+
+/// Dictionary in the source format of `MeCab`, as found in the distributions of IPADIC and `UniDic`.
+///
+/// The folder must hold the lexicon as CSV files, along with `matrix.def`, `char.def`
+/// and `unk.def`.
+pub struct Dictionary {
+  words: HashMap<Box<str>, Vec<Entry>>,
+
+  /// Length in characters of the longest word.
+  max_len: usize,
+
+  matrix: Matrix,
+  categories: Vec<Category>,
+  ranges: Vec<CharRange>,
+  default_category: usize,
+}
+
+/// A word of the lexicon, or a template for unknown words.
+pub struct Entry {
+  pub left_id: u16,
+  pub right_id: u16,
+  pub cost: i16,
+
+  /// Part of speech, base form, reading and so on, separated by tabs.
+  pub features: Box<str>,
+}
+
+/// How a character category is handled when no word, or not only known words, start with it.
+pub struct Category {
+  /// Whether unknown words are always looked for, even when there are known ones.
+  pub invoke: bool,
+
+  /// Whether a whole run of characters of the category makes an unknown word.
+  pub group: bool,
+
+  /// Unknown words of up to this many characters are also made.
+  pub length: usize,
+
+  pub unknown: Vec<Entry>,
+}
+
+struct CharRange {
+  first: u32,
+  last: u32,
+  category: usize,
+}
+
+/// Cost of connecting the right context of a word to the left context of the next one.
+struct Matrix {
+  right_size: usize,
+  left_size: usize,
+  costs: Vec<i16>,
+}
+
+impl Dictionary {
+  pub fn read(dir: &Path) -> Result<Self> {
+    let mut lexicons = Vec::new();
+    for file in fs::read_dir(dir)? {
+      let path = file?.path();
+      if is_lexicon_file(&path) {
+        lexicons.push((path.display().to_string(), read_text(&path)?));
+      }
+    }
+
+    if lexicons.is_empty() {
+      bail!("No lexicon was found in {}", dir.display());
+    }
+
+    Self::parse(
+      &read_text(&dir.join("matrix.def"))?,
+      &read_text(&dir.join("char.def"))?,
+      &read_text(&dir.join("unk.def"))?,
+      lexicons
+        .iter()
+        .map(|(name, text)| (name.as_str(), text.as_str())),
+    )
+  }
+
+  pub fn bundled() -> Result<Self> {
+    let file = |name: &str| {
+      BUNDLED
+        .iter()
+        .find(|(it, _)| *it == name)
+        .map_or("", |(_, text)| text)
+    };
+
+    let lexicons = BUNDLED
+      .iter()
+      .copied()
+      .filter(|(name, _)| is_lexicon_file(Path::new(name)));
+
+    Self::parse(
+      file("matrix.def"),
+      file("char.def"),
+      file("unk.def"),
+      lexicons,
+    )
+  }
+
+  /// Parses the dictionary from the text of its files, where each lexicon comes with its name.
+  fn parse<'a>(
+    matrix: &str,
+    char_def: &str,
+    unk_def: &str,
+    lexicons: impl IntoIterator<Item = (&'a str, &'a str)>,
+  ) -> Result<Self> {
+    let matrix = Matrix::parse(matrix)?;
+    let (mut categories, names, ranges) = parse_char_def(char_def)?;
+
+    let mut words: HashMap<Box<str>, Vec<Entry>> = HashMap::new();
+    for (name, text) in lexicons {
+      for (surface, entry) in
+        parse_entries(text).with_context(|| format!("Invalid lexicon: {name}"))?
+      {
+        words.entry(surface).or_default().push(entry);
+      }
+    }
+
+    if words.is_empty() {
+      bail!("The lexicon is empty");
+    }
+
+    for (name, entry) in parse_entries(unk_def)? {
+      if let Some(index) = names.get(&name) {
+        categories[*index].unknown.push(entry);
+      }
+    }
+
+    let Some(default_category) = names.get("DEFAULT").copied() else {
+      bail!("The DEFAULT character category is missing");
+    };
+
+    let max_len = words
+      .keys()
+      .map(|surface| surface.chars().count())
+      .max()
+      .unwrap_or(0);
+
+    Ok(Self {
+      words,
+      max_len,
+      matrix,
+      categories,
+      ranges,
+      default_category,
+    })
+  }
+
+  pub fn lookup(&self, surface: &str) -> &[Entry] {
+    self
+      .words
+      .get(surface)
+      .map_or(&[], Vec::as_slice)
+  }
+
+  pub fn max_len(&self) -> usize {
+    self.max_len
+  }
+
+  /// Returns the index of the category of the character, where later ranges of `char.def`
+  /// take precedence over earlier ones.
+  pub fn category_of(&self, c: char) -> usize {
+    let c = u32::from(c);
+    self
+      .ranges
+      .iter()
+      .rev()
+      .find(|range| range.first <= c && c <= range.last)
+      .map_or(self.default_category, |range| range.category)
+  }
+
+  pub fn category(&self, index: usize) -> &Category {
+    &self.categories[index]
+  }
+
+  pub fn connection_cost(&self, right_id: u16, left_id: u16) -> i32 {
+    self.matrix.cost(right_id, left_id)
+  }
+}
+
+impl Matrix {
+  fn parse(text: &str) -> Result<Self> {
+    let mut lines = text.lines();
+    let Some((right_size, left_size)) = lines
+      .next()
+      .and_then(|header| header.split_once(char::is_whitespace))
+    else {
+      bail!("Invalid connection matrix header");
+    };
+
+    let right_size: usize = right_size.trim().parse()?;
+    let left_size: usize = left_size.trim().parse()?;
+    let mut costs = vec![0; right_size.saturating_mul(left_size)];
+
+    for line in lines {
+      let mut fields = line.split_whitespace();
+      let (Some(right), Some(left), Some(cost)) = (fields.next(), fields.next(), fields.next())
+      else {
+        continue;
+      };
+
+      let index = right
+        .parse::<usize>()?
+        .saturating_mul(left_size)
+        .saturating_add(left.parse()?);
+
+      if let Some(slot) = costs.get_mut(index) {
+        *slot = cost.parse()?;
+      }
+    }
+
+    Ok(Self { right_size, left_size, costs })
+  }
+
+  fn cost(&self, right_id: u16, left_id: u16) -> i32 {
+    let (right, left) = (usize::from(right_id), usize::from(left_id));
+    if right < self.right_size && left < self.left_size {
+      i32::from(self.costs[right * self.left_size + left])
+    } else {
+      0
+    }
+  }
+}
+
+type CharDef = (Vec<Category>, HashMap<Box<str>, usize>, Vec<CharRange>);
+
+/// Parses the category definitions, such as `KANJI 0 0 2`, and the ranges of characters
+/// assigned to them, such as `0x4E00..0x9FA5 KANJI`. Only the first category of a range counts.
+fn parse_char_def(text: &str) -> Result<CharDef> {
+  let mut categories = Vec::new();
+  let mut names: HashMap<Box<str>, usize> = HashMap::new();
+  let mut ranges = Vec::new();
+
+  for line in text.lines() {
+    let line = line
+      .split_once('#')
+      .map_or(line, |(line, _)| line)
+      .trim();
+
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let Some(first) = fields.first() else {
+      continue;
+    };
+
+    if let Some(hex) = first.strip_prefix("0x") {
+      let Some(name) = fields.get(1) else {
+        continue;
+      };
+
+      let (first, last) = hex.split_once("..0x").unwrap_or((hex, hex));
+      let Some(category) = names.get(*name).copied() else {
+        bail!("Unknown character category: {name}");
+      };
+
+      ranges.push(CharRange {
+        first: u32::from_str_radix(first, 16)?,
+        last: u32::from_str_radix(last, 16)?,
+        category,
+      });
+    } else if let [name, invoke, group, length, ..] = fields.as_slice() {
+      names.insert(Box::from(*name), categories.len());
+      categories.push(Category {
+        invoke: *invoke == "1",
+        group: *group == "1",
+        length: length.parse()?,
+        unknown: Vec::new(),
+      });
+    }
+  }
+
+  Ok((categories, names, ranges))
+}
+
+/// Parses lines of the form `surface,left_id,right_id,cost,features...`.
+fn parse_entries(text: &str) -> Result<Vec<(Box<str>, Entry)>> {
+  let mut entries = Vec::new();
+  for line in text.lines() {
+    let fields = split_fields(line, ',');
+    let [surface, left_id, right_id, cost, features @ ..] = fields.as_slice() else {
+      continue;
+    };
+
+    if surface.is_empty() {
+      continue;
+    }
+
+    entries.push((
+      Box::from(surface.as_str()),
+      Entry {
+        left_id: left_id.parse()?,
+        right_id: right_id.parse()?,
+        cost: cost.parse()?,
+        features: Box::from(features.join("\t")),
+      },
+    ));
+  }
+
+  Ok(entries)
+}
+
+/// Whether the file is read along with the dictionary, so that changing it changes the dictionary.
+pub fn is_dictionary_file(path: &Path) -> bool {
+  let name = path.file_name().and_then(OsStr::to_str);
+  is_lexicon_file(path) || matches!(name, Some("matrix.def" | "char.def" | "unk.def"))
+}
+
+fn is_lexicon_file(path: &Path) -> bool {
+  path.extension() == Some(OsStr::new("csv"))
+}
+
+/// The files of IPADIC are encoded in EUC-JP, while those of `UniDic` are in UTF-8.
+fn read_text(path: &Path) -> Result<String> {
+  let bytes = fs::read(path).with_context(|| format!("Could not read {}", path.display()))?;
+  encoding::decode(&bytes, None)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CHAR_DEF: &str = "\
+DEFAULT 0 1 0
+KANJI 0 0 2 # comment
+KANJINUMERIC 1 1 0
+
+0x4E00..0x9FFF KANJI
+0x4E00 KANJINUMERIC KANJI
+";
+
+  #[test]
+  fn reads_char_def() {
+    let (categories, names, ranges) = parse_char_def(CHAR_DEF).unwrap();
+    assert_eq!(categories.len(), 3);
+    assert_eq!(ranges.len(), 2);
+
+    let kanji = &categories[names["KANJI"]];
+    assert!(!kanji.invoke && !kanji.group);
+    assert_eq!(kanji.length, 2);
+  }
+
+  #[test]
+  fn rejects_unknown_categories() {
+    let err = parse_char_def("DEFAULT 0 1 0\n0x0041..0x005A ALPHA\n")
+      .map(drop)
+      .unwrap_err();
+    assert_eq!(err.to_string(), "Unknown character category: ALPHA");
+  }
+
+  #[test]
+  fn reads_matrix() {
+    let matrix = Matrix::parse("2 3\n0 0 10\n0 2 -5\n1 1 7\n").unwrap();
+    assert_eq!(matrix.cost(0, 0), 10);
+    assert_eq!(matrix.cost(0, 2), -5);
+    assert_eq!(matrix.cost(1, 1), 7);
+    assert_eq!(matrix.cost(1, 0), 0);
+    assert_eq!(matrix.cost(2, 0), 0);
+  }
+
+  #[test]
+  fn reads_dictionary() {
+    let dictionary = Dictionary::parse(
+      "1 1\n0 0 0\n",
+      CHAR_DEF,
+      "KANJI,0,0,7000,名詞,一般\nALPHA,0,0,4000,名詞,固有名詞\n",
+      [(
+        "a.csv",
+        "日本,0,0,3000,名詞,\"固有,名詞\"\n日本語,0,0,2000,名詞,一般\n",
+      )],
+    )
+    .unwrap();
+
+    assert_eq!(dictionary.max_len(), 3);
+    assert_eq!(
+      dictionary.lookup("日本")[0]
+        .features
+        .as_ref(),
+      "名詞\t固有,名詞"
+    );
+    assert!(dictionary.lookup("本").is_empty());
+
+    assert_eq!(dictionary.category_of('日'), 1);
+    assert_eq!(dictionary.category_of('一'), 2);
+    assert_eq!(dictionary.category_of('a'), 0);
+    assert_eq!(dictionary.category(1).unknown.len(), 1);
+  }
+
+  #[test]
+  fn reads_bundled_dictionary() {
+    let dictionary = Dictionary::bundled().unwrap();
+    let went = dictionary.lookup("行っ");
+    assert!(
+      went
+        .iter()
+        .any(|entry| entry.features.contains("行く"))
+    );
+  }
+}
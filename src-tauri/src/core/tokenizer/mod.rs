@@ -0,0 +1,353 @@
+mod dictionary;
+
+use crate::core::snippet::Token;
+use crate::settings;
+use anyhow::Result;
+use dictionary::{Dictionary, Entry};
+use itertools::Itertools;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::nonpoison::Mutex;
+use std::time::UNIX_EPOCH;
+use tauri::AppHandle;
+
+/// The dictionary is only read again when its identity changes.
+static TOKENIZER: Mutex<Option<Arc<Tokenizer>>> = Mutex::new(None);
+
+/// Fixed cost of the characters for which not even an unknown word could be made.
+const FALLBACK_COST: i32 = 10_000;
+
+/// Unknown words made of a whole run of characters are never longer than this.
+const MAX_GROUP_LEN: usize = 256;
+
+/// Morphological analyzer that splits Japanese text into words with the Viterbi algorithm,
+/// using a dictionary in the format of `MeCab`.
+pub struct Tokenizer {
+  identity: String,
+  dictionary: Dictionary,
+}
+
+/// Returns the tokenizer for the dictionary in the settings, or for the small one bundled
+/// with the app if none is set.
+pub fn load(app: &AppHandle) -> Result<Arc<Tokenizer>> {
+  let path = settings::tokenizer_dictionary(app);
+  let identity = match &path {
+    Some(path) => identity_of(path)?,
+    None => bundled_identity(),
+  };
+
+  let mut cached = TOKENIZER.lock();
+  if let Some(tokenizer) = cached.as_ref()
+    && tokenizer.identity == identity
+  {
+    return Ok(Arc::clone(tokenizer));
+  }
+
+  let dictionary = match &path {
+    Some(path) => Dictionary::read(path)?,
+    None => Dictionary::bundled()?,
+  };
+
+  let tokenizer = Arc::new(Tokenizer { identity, dictionary });
+  *cached = Some(Arc::clone(&tokenizer));
+
+  Ok(tokenizer)
+}
+
+struct Node<'a> {
+  start: usize,
+  end: usize,
+  right_id: u16,
+  features: Option<&'a str>,
+  total: i64,
+  previous: usize,
+}
+
+impl Tokenizer {
+  /// Identifies the dictionary, so that files are tokenized again when it changes.
+  pub fn identity(&self) -> &str {
+    &self.identity
+  }
+
+  /// Splits the text into tokens, whose offsets are counted in characters.
+  /// Whitespace is left out.
+  pub fn tokenize(&self, text: &str) -> Vec<Token> {
+    let chars = text.chars().collect_vec();
+    let offsets = text
+      .char_indices()
+      .map(|(offset, _)| offset)
+      .chain([text.len()])
+      .collect_vec();
+
+    let mut nodes = vec![Node {
+      start: 0,
+      end: 0,
+      right_id: 0,
+      features: None,
+      total: 0,
+      previous: 0,
+    }];
+
+    let mut ending_at: Vec<Vec<usize>> = vec![Vec::new(); chars.len() + 1];
+    ending_at[0].push(0);
+
+    for start in 0..chars.len() {
+      if ending_at[start].is_empty() {
+        continue;
+      }
+
+      let mut candidates: Vec<(usize, &Entry)> = Vec::new();
+      let max_len = self
+        .dictionary
+        .max_len()
+        .min(chars.len() - start);
+
+      for len in 1..=max_len {
+        let surface = &text[offsets[start]..offsets[start + len]];
+        for entry in self.dictionary.lookup(surface) {
+          candidates.push((start + len, entry));
+        }
+      }
+
+      let index = self.dictionary.category_of(chars[start]);
+      let category = self.dictionary.category(index);
+      if category.invoke || candidates.is_empty() {
+        let run = chars[start..]
+          .iter()
+          .take(MAX_GROUP_LEN)
+          .take_while(|c| self.dictionary.category_of(**c) == index)
+          .count();
+
+        let mut lengths = (1..=category.length.min(run)).collect_vec();
+        if category.group && !lengths.contains(&run) {
+          lengths.push(run);
+        }
+
+        for len in lengths {
+          for entry in &category.unknown {
+            candidates.push((start + len, entry));
+          }
+        }
+      }
+
+      if candidates.is_empty() {
+        self.push_node(&mut nodes, &mut ending_at, start, start + 1, None);
+      }
+
+      for (end, entry) in candidates {
+        self.push_node(&mut nodes, &mut ending_at, start, end, Some(entry));
+      }
+    }
+
+    let last = ending_at[chars.len()]
+      .iter()
+      .copied()
+      .min_by_key(|index| {
+        let node = &nodes[*index];
+        node.total
+          + i64::from(
+            self
+              .dictionary
+              .connection_cost(node.right_id, 0),
+          )
+      })
+      .unwrap_or(0);
+
+    let mut path = Vec::new();
+    let mut index = last;
+    while index != 0 {
+      path.push(index);
+      index = nodes[index].previous;
+    }
+
+    path
+      .into_iter()
+      .rev()
+      .map(|index| &nodes[index])
+      .filter(|node| {
+        !chars[node.start..node.end]
+          .iter()
+          .all(|c| c.is_whitespace())
+      })
+      .map(|node| {
+        let surface = &text[offsets[node.start]..offsets[node.end]];
+        token(node.start, node.end - node.start, surface, node.features)
+      })
+      .collect()
+  }
+
+  fn push_node<'a>(
+    &self,
+    nodes: &mut Vec<Node<'a>>,
+    ending_at: &mut [Vec<usize>],
+    start: usize,
+    end: usize,
+    entry: Option<&'a Entry>,
+  ) {
+    let (left_id, right_id, cost) = entry.map_or((0, 0, FALLBACK_COST), |entry| {
+      (entry.left_id, entry.right_id, i32::from(entry.cost))
+    });
+
+    let best = ending_at[start]
+      .iter()
+      .copied()
+      .map(|index| {
+        let previous = &nodes[index];
+        let connection = self
+          .dictionary
+          .connection_cost(previous.right_id, left_id);
+        (index, previous.total + i64::from(connection))
+      })
+      .min_by_key(|(_, total)| *total);
+
+    if let Some((previous, total)) = best {
+      ending_at[end].push(nodes.len());
+      nodes.push(Node {
+        start,
+        end,
+        right_id,
+        features: entry.map(|entry| &*entry.features),
+        total: total + i64::from(cost),
+        previous,
+      });
+    }
+  }
+}
+
+/// Identifies a dictionary by its folder along with the size and modification time of each
+/// of its files, so that editing them in place counts as a change too.
+fn identity_of(dir: &Path) -> Result<String> {
+  let mut stamps = Vec::new();
+  for file in fs::read_dir(dir)? {
+    let path = file?.path();
+    if !dictionary::is_dictionary_file(&path) {
+      continue;
+    }
+
+    let metadata = fs::metadata(&path)?;
+    let modified = metadata
+      .modified()?
+      .duration_since(UNIX_EPOCH)?
+      .as_nanos();
+
+    let name = path
+      .file_name()
+      .unwrap_or_default()
+      .to_string_lossy();
+    stamps.push(format!("{name}:{}:{modified}", metadata.len()));
+  }
+
+  stamps.sort_unstable();
+  Ok(format!("{}|{}", dir.display(), stamps.join(",")))
+}
+
+/// Identifies the bundled dictionary by a hash of its files, which only changes along with them.
+fn bundled_identity() -> String {
+  // FNV-1a, since the hashers of the standard library may change between releases.
+  let hash = dictionary::BUNDLED
+    .iter()
+    .flat_map(|(_, text)| text.bytes())
+    .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+      (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+
+  format!("bundled|{hash:016x}")
+}
+
+/// Builds a token from the features of its entry, whose layout depends on the dictionary.
+/// IPADIC has the base form and the reading at the seventh and eighth places, while `UniDic`
+/// has more features, with the lemma and the pronunciation at the eighth and tenth places.
+fn token(start: usize, len: usize, surface: &str, features: Option<&str>) -> Token {
+  let features = features
+    .unwrap_or_default()
+    .split('\t')
+    .collect_vec();
+
+  let field = |index: usize| {
+    features
+      .get(index)
+      .filter(|value| !value.is_empty() && **value != "*")
+      .map(|value| (*value).to_owned())
+  };
+
+  let pos = features
+    .iter()
+    .take(4)
+    .filter(|value| !value.is_empty() && **value != "*")
+    .join("-");
+
+  let (lemma, reading) =
+    if features.len() >= 13 { (field(7), field(9)) } else { (field(6), field(7)) };
+
+  Token {
+    start,
+    len,
+    surface: surface.to_owned(),
+    pos,
+    lemma,
+    reading,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn bundled() -> Tokenizer {
+    Tokenizer {
+      identity: bundled_identity(),
+      dictionary: Dictionary::bundled().unwrap(),
+    }
+  }
+
+  fn surfaces(tokens: &[Token]) -> Vec<&str> {
+    tokens
+      .iter()
+      .map(|token| token.surface.as_str())
+      .collect()
+  }
+
+  #[test]
+  fn splits_sentences_into_words() {
+    let tokens = bundled().tokenize("昨日、友達と東京へ行った。");
+    assert_eq!(
+      surfaces(&tokens),
+      ["昨日", "、", "友達", "と", "東京", "へ", "行っ", "た", "。"]
+    );
+
+    let went = &tokens[6];
+    assert_eq!(went.lemma.as_deref(), Some("行く"));
+    assert_eq!(went.reading.as_deref(), Some("イッ"));
+    assert_eq!(went.pos, "動詞-自立");
+  }
+
+  #[test]
+  fn counts_offsets_in_characters_without_whitespace() {
+    let tokens = bundled().tokenize("  犬 と 猫");
+    let offsets = tokens
+      .iter()
+      .map(|token| (token.surface.as_str(), token.start, token.len))
+      .collect_vec();
+
+    assert_eq!(offsets, [("犬", 2, 1), ("と", 4, 1), ("猫", 6, 1)]);
+  }
+
+  #[test]
+  fn groups_unknown_words() {
+    let tokens = bundled().tokenize("カタカナのテキスト");
+    assert_eq!(surfaces(&tokens), ["カタカナ", "の", "テキスト"]);
+    assert_eq!(tokens[0].lemma, None);
+    assert_eq!(tokens[0].pos, "名詞-一般");
+  }
+
+  #[test]
+  fn reads_unidic_features() {
+    let features =
+      "動詞\t一般\t*\t*\t五段-カ行\t連用形-促音便\tイク\t行く\t行っ\tイッ\t行く\tイク\t和";
+    let token = token(0, 2, "行っ", Some(features));
+    assert_eq!(token.pos, "動詞-一般");
+    assert_eq!(token.lemma.as_deref(), Some("行く"));
+    assert_eq!(token.reading.as_deref(), Some("イッ"));
+  }
+}
@@ -0,0 +1,129 @@
+use crate::core::kanji::counts_as_kanji;
+use crate::database::sql_types::KanjiChar;
+use crate::settings;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use tauri::AppHandle;
+
+static VARIANTS: LazyLock<Variants> =
+  LazyLock::new(|| Variants::parse(include_str!("../../data/variants.txt")));
+
+/// Variant forms of kanji, such as the traditional `國` for `国`.
+struct Variants {
+  canonical: HashMap<char, char>,
+  forms: HashMap<char, Vec<char>>,
+}
+
+impl Variants {
+  /// Each line of the table holds a canonical form followed by its variants.
+  fn parse(table: &str) -> Self {
+    let mut canonical = HashMap::new();
+    let mut forms = HashMap::new();
+
+    for line in table.lines() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      let group = line
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+
+      if let Some((&first, variants)) = group.split_first() {
+        for variant in variants {
+          canonical.insert(*variant, first);
+        }
+
+        forms.insert(first, group);
+      }
+    }
+
+    Self { canonical, forms }
+  }
+}
+
+/// Returns the canonical form of the kanji, which is the kanji itself if it is not a variant.
+pub fn canonical(kanji: KanjiChar) -> KanjiChar {
+  VARIANTS
+    .canonical
+    .get(&*kanji)
+    .copied()
+    .and_then(KanjiChar::from_char)
+    .unwrap_or(kanji)
+}
+
+/// Returns the kanji that the character is counted as in stats, following the settings on
+/// iteration marks and variants, or `None` if it is not counted at all.
+pub fn fold(
+  kanji: KanjiChar,
+  iteration_marks: bool,
+  normalize_variants: bool,
+) -> Option<KanjiChar> {
+  if !counts_as_kanji(*kanji, iteration_marks) {
+    None
+  } else if normalize_variants {
+    Some(canonical(kanji))
+  } else {
+    Some(kanji)
+  }
+}
+
+/// Returns every form of the kanji, starting with the canonical one.
+pub fn forms(kanji: KanjiChar) -> Vec<KanjiChar> {
+  let canonical = canonical(kanji);
+  VARIANTS
+    .forms
+    .get(&*canonical)
+    .map(|forms| {
+      forms
+        .iter()
+        .copied()
+        .filter_map(KanjiChar::from_char)
+        .collect()
+    })
+    .unwrap_or_else(|| vec![canonical])
+}
+
+/// Returns the forms of the kanji to look for, which are all of its variants
+/// only if normalization is enabled in the settings.
+pub fn search_forms(app: &AppHandle, kanji: KanjiChar) -> Vec<KanjiChar> {
+  if settings::normalize_variants(app) { forms(kanji) } else { vec![kanji] }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn kanji(c: char) -> KanjiChar {
+    KanjiChar::from_char(c).unwrap()
+  }
+
+  #[test]
+  fn folds_variants_if_enabled() {
+    assert_eq!(fold(kanji('國'), false, true), Some(kanji('国')));
+    assert_eq!(fold(kanji('國'), false, false), Some(kanji('國')));
+    assert_eq!(fold(kanji('国'), false, true), Some(kanji('国')));
+  }
+
+  #[test]
+  fn folds_iteration_marks_if_enabled() {
+    assert_eq!(fold(kanji('々'), false, true), None);
+    assert_eq!(fold(kanji('々'), true, true), Some(kanji('々')));
+  }
+
+  #[test]
+  fn lists_forms_from_the_canonical_one() {
+    assert_eq!(forms(kanji('國')), [kanji('国'), kanji('圀'), kanji('國')]);
+    assert_eq!(forms(kanji('日')), [kanji('日')]);
+  }
+
+  #[test]
+  fn parses_variant_groups() {
+    let variants = Variants::parse("# comment\n\n学 學\n国 圀國\n");
+    assert_eq!(variants.canonical[&'國'], '国');
+    assert_eq!(variants.forms[&'学'], ['学', '學']);
+    assert!(!variants.canonical.contains_key(&'学'));
+  }
+}
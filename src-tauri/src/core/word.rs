@@ -0,0 +1,238 @@
+use crate::core::kanji::{is_iteration_mark, is_kanji};
+use crate::core::scan::blocking_scan;
+use crate::core::snippet::Token;
+use crate::core::task::Task;
+use crate::core::variant;
+use crate::database::model::source::Source;
+use crate::database::sql_types::{KanjiChar, SourceId};
+use crate::manager::ManagerExt;
+use crate::settings;
+use anyhow::Result;
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::AppHandle;
+use tauri::async_runtime::spawn_blocking;
+
+/// Okurigana longer than this are cut short, since they are usually followed by inflections.
+const MAX_OKURIGANA: usize = 3;
+
+/// Hiragana that most likely start a particle instead of continuing the word.
+const PARTICLES: &[char] = &['の', 'を', 'に', 'が', 'は', 'で', 'と', 'も', 'へ', 'や'];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordStats {
+  word: Arc<str>,
+  seen: u32,
+  ratio: f64,
+  kanjis: Vec<KanjiChar>,
+
+  /// Whether the word ends with okurigana, as in `食べる`.
+  okurigana: bool,
+
+  sources: Vec<WordStatsSource>,
+}
+
+impl WordStats {
+  fn new(word: &str) -> WordStats {
+    WordStats {
+      word: Arc::from(word),
+      seen: 0,
+      ratio: 0.0,
+      kanjis: kanjis_of(word),
+      okurigana: has_okurigana(word),
+      sources: Vec::default(),
+    }
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WordStatsSource {
+  id: SourceId,
+  name: Arc<str>,
+  seen: u32,
+}
+
+/// Extracts the candidate words of the text, which are runs of at least two kanji, such as
+/// `勉強`, and runs of kanji followed by okurigana, such as `食べる` or `勉強する`.
+/// Each occurrence yields only its longest word, so that `勉強する` is not counted as `勉強` too.
+pub fn extract(text: &str) -> Vec<String> {
+  let chars = text.chars().collect_vec();
+  let mut words = Vec::new();
+  let mut index = 0;
+
+  while let Some(&c) = chars.get(index) {
+    // A word never starts with an iteration mark.
+    if KanjiChar::from_char(c).is_none() || is_iteration_mark(c) {
+      index += 1;
+      continue;
+    }
+
+    let start = index;
+    while chars
+      .get(index)
+      .is_some_and(|c| KanjiChar::from_char(*c).is_some())
+    {
+      index += 1;
+    }
+
+    let okurigana = chars[index..]
+      .iter()
+      .take_while(|c| is_hiragana(**c) && !PARTICLES.contains(c))
+      .take(MAX_OKURIGANA)
+      .count();
+
+    if okurigana > 0 || index - start >= 2 {
+      words.push(
+        chars[start..index + okurigana]
+          .iter()
+          .collect(),
+      );
+    }
+  }
+
+  words
+}
+
+/// Returns the dictionary forms of the tokens that are made of at least two characters,
+/// one of which is a kanji, such as `勉強` or `食べる`.
+pub fn from_tokens(tokens: &[Token]) -> Vec<String> {
+  tokens
+    .iter()
+    .map(|token| {
+      token
+        .lemma
+        .as_deref()
+        .unwrap_or(&token.surface)
+    })
+    .filter(|word| word.chars().count() >= 2 && word.chars().any(is_kanji))
+    .map(ToOwned::to_owned)
+    .collect()
+}
+
+pub fn has_okurigana(word: &str) -> bool {
+  word
+    .chars()
+    .next_back()
+    .is_some_and(is_hiragana)
+}
+
+/// Returns the word without its okurigana, if what remains is still a word of at least
+/// two characters, such as `勉強` for `勉強する`.
+fn stem_of(word: &str) -> Option<&str> {
+  let stem = word.trim_end_matches(is_hiragana);
+  (stem.chars().count() >= 2).then_some(stem)
+}
+
+fn is_hiragana(c: char) -> bool {
+  matches!(c, 'ぁ'..='ゖ')
+}
+
+fn kanjis_of(word: &str) -> Vec<KanjiChar> {
+  word
+    .chars()
+    .filter_map(KanjiChar::from_char)
+    .unique()
+    .collect()
+}
+
+pub async fn search(app: AppHandle, task: Task) -> Result<Vec<WordStats>> {
+  spawn_blocking(move || blocking_search(&app, &task)).await?
+}
+
+fn blocking_search(app: &AppHandle, task: &Task) -> Result<Vec<WordStats>> {
+  let sources = app.database().get_enabled_sources()?;
+  blocking_search_with_options(app)
+    .sources(&sources)
+    .task(task)
+    .call()
+}
+
+/// Counts the words of the sources, from the most common, folding variant kanji if enabled.
+/// Okurigana words not found by a tokenizer are only kept if enabled, or else as their stem.
+#[bon::builder]
+pub fn blocking_search_with_options(
+  #[builder(start_fn)] app: &AppHandle,
+  #[builder(default)] sources: &[Source],
+  task: Option<&Task>,
+) -> Result<Vec<WordStats>> {
+  let db = app.database();
+  let task = task.cloned().unwrap_or_default();
+  blocking_scan(app, sources, &task)?;
+
+  let names: HashMap<SourceId, Arc<str>> = sources
+    .iter()
+    .map(|source| (source.id, Arc::from(source.name.as_str())))
+    .collect();
+
+  let ids = names.keys().copied().collect_vec();
+  let okurigana = settings::word_okurigana(app);
+  let normalize_variants = settings::normalize_variants(app);
+  let mut words: HashMap<String, WordStats> = HashMap::new();
+
+  for (word, id, tokenized, seen) in db.get_word_counts(&ids)? {
+    task.check()?;
+
+    // Without okurigana, words such as `勉強する` still count as their stem.
+    let word = if okurigana || tokenized || !has_okurigana(&word) {
+      word
+    } else if let Some(stem) = stem_of(&word) {
+      stem.to_owned()
+    } else {
+      continue;
+    };
+
+    let word = if normalize_variants {
+      word
+        .chars()
+        .map(|c| KanjiChar::from_char(c).map_or(c, |kanji| *variant::canonical(kanji)))
+        .collect()
+    } else {
+      word
+    };
+
+    let seen = u32::try_from(seen).unwrap_or(u32::MAX);
+    let stats = words
+      .entry(word)
+      .or_insert_with_key(|word| WordStats::new(word));
+
+    stats.seen = stats.seen.saturating_add(seen);
+
+    if let Some(source) = stats
+      .sources
+      .iter_mut()
+      .find(|source| source.id == id)
+    {
+      source.seen = source.seen.saturating_add(seen);
+    } else {
+      let name = Arc::clone(&names[&id]);
+      stats
+        .sources
+        .push(WordStatsSource { id, name, seen });
+    }
+  }
+
+  let total = words
+    .values()
+    .map(|word| u64::from(word.seen))
+    .fold(0u64, u64::saturating_add) as f64;
+
+  let words = words
+    .into_values()
+    .update(|word| {
+      if total.is_normal() {
+        word.ratio = f64::from(word.seen) / total;
+      }
+    })
+    .sorted_unstable_by(|a, b| {
+      b.seen
+        .cmp(&a.seen)
+        .then_with(|| a.word.cmp(&b.word))
+    })
+    .collect();
+
+  Ok(words)
+}
@@ -10,7 +10,8 @@ impl DatabaseHandle {
     diesel::insert_into(kanji)
       .values(new)
       .on_conflict(id)
-      .do_nothing()
+      .do_update()
+      .set(updated_at.eq(&new.updated_at))
       .execute(&mut *self.conn())
       .map(drop)
       .map_err(Into::into)
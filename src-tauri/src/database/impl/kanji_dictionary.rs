@@ -0,0 +1,45 @@
+use crate::database::model::kanji_dictionary::KanjiDictionaryEntry;
+use crate::database::sql_types::KanjiChar;
+use crate::database::{DatabaseHandle, schema};
+use anyhow::Result;
+use diesel::prelude::*;
+
+impl DatabaseHandle {
+  pub fn get_kanji_dictionary(&self) -> Result<Vec<KanjiDictionaryEntry>> {
+    use schema::kanji_dictionary::dsl::*;
+    kanji_dictionary
+      .select(KanjiDictionaryEntry::as_select())
+      .load(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  pub fn get_kanji_dictionary_entry(
+    &self,
+    kanji: KanjiChar,
+  ) -> Result<Option<KanjiDictionaryEntry>> {
+    use schema::kanji_dictionary::dsl::*;
+    kanji_dictionary
+      .find(kanji)
+      .select(KanjiDictionaryEntry::as_select())
+      .first(&mut *self.conn())
+      .optional()
+      .map_err(Into::into)
+  }
+
+  /// Replaces the whole dictionary with the given entries.
+  pub fn replace_kanji_dictionary(&self, entries: &[KanjiDictionaryEntry]) -> Result<usize> {
+    use schema::kanji_dictionary::dsl::*;
+    self.conn().transaction(|conn| {
+      diesel::delete(kanji_dictionary).execute(conn)?;
+
+      let mut rows = 0;
+      for chunk in entries.chunks(1000) {
+        rows += diesel::insert_into(kanji_dictionary)
+          .values(chunk)
+          .execute(conn)?;
+      }
+
+      Ok(rows)
+    })
+  }
+}
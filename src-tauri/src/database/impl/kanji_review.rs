@@ -0,0 +1,37 @@
+use crate::database::model::kanji_review::KanjiReview;
+use crate::database::sql_types::KanjiChar;
+use crate::database::{DatabaseHandle, schema};
+use anyhow::Result;
+use diesel::prelude::*;
+
+impl DatabaseHandle {
+  pub fn get_kanji_review(&self, kanji: KanjiChar) -> Result<Option<KanjiReview>> {
+    use schema::kanji_review::dsl::*;
+    kanji_review
+      .find(kanji)
+      .select(KanjiReview::as_select())
+      .first(&mut *self.conn())
+      .optional()
+      .map_err(Into::into)
+  }
+
+  pub fn get_kanji_reviews(&self) -> Result<Vec<KanjiReview>> {
+    use schema::kanji_review::dsl::*;
+    kanji_review
+      .select(KanjiReview::as_select())
+      .load(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  pub fn set_kanji_review(&self, review: &KanjiReview) -> Result<()> {
+    use schema::kanji_review::dsl::*;
+    diesel::insert_into(kanji_review)
+      .values(review)
+      .on_conflict(id)
+      .do_update()
+      .set(review)
+      .execute(&mut *self.conn())
+      .map(drop)
+      .map_err(Into::into)
+  }
+}
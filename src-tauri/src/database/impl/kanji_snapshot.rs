@@ -0,0 +1,144 @@
+use crate::database::model::kanji_snapshot::{KanjiSnapshot, KanjiSnapshotKanji, NewKanjiSnapshot};
+use crate::database::sql_types::{KanjiChar, KanjiSnapshotId, SourceId, Zoned};
+use crate::database::{DatabaseHandle, schema};
+use anyhow::Result;
+use diesel::dsl::{min, sum};
+use diesel::prelude::*;
+use itertools::Itertools;
+
+impl DatabaseHandle {
+  /// Records the current kanji counts of the source. A snapshot taken on the same day
+  /// replaces the previous one, so that frequent rescans do not pile up.
+  pub fn create_kanji_snapshot(&self, source: SourceId) -> Result<KanjiSnapshotId> {
+    use schema::kanji_snapshot::dsl::*;
+    let now = Zoned::now();
+    self.conn().transaction(|conn| {
+      let latest = kanji_snapshot
+        .filter(source_id.eq(source))
+        .order(id.desc())
+        .select(KanjiSnapshot::as_select())
+        .first(conn)
+        .optional()?;
+
+      if let Some(latest) = latest
+        && latest.created_at.date() == now.date()
+      {
+        delete_kanji_snapshots(conn, &[latest.id])?;
+      }
+
+      let snapshot_id = diesel::insert_into(kanji_snapshot)
+        .values(NewKanjiSnapshot { source_id: source, created_at: now })
+        .returning(id)
+        .get_result(conn)?;
+
+      let kanjis = {
+        use schema::source_file_kanji::dsl::*;
+        source_file_kanji
+          .filter(source_id.eq(source))
+          .group_by(kanji)
+          .select((kanji, sum(seen)))
+          .load::<(KanjiChar, Option<i64>)>(conn)?
+          .into_iter()
+          .filter_map(|(character, count)| {
+            let count = count.filter(|it| *it > 0)?;
+            Some(KanjiSnapshotKanji {
+              snapshot_id,
+              kanji: character,
+              seen: count,
+            })
+          })
+          .collect_vec()
+      };
+
+      for chunk in kanjis.chunks(1000) {
+        diesel::insert_into(schema::kanji_snapshot_kanji::table)
+          .values(chunk)
+          .execute(conn)?;
+      }
+
+      Ok(snapshot_id)
+    })
+  }
+
+  pub fn has_kanji_snapshot(&self, source: SourceId) -> Result<bool> {
+    use schema::kanji_snapshot::dsl::*;
+    diesel::select(diesel::dsl::exists(
+      kanji_snapshot.filter(source_id.eq(source)),
+    ))
+    .get_result(&mut *self.conn())
+    .map_err(Into::into)
+  }
+
+  /// Returns the snapshots of the sources, from the oldest to the newest.
+  pub fn get_kanji_snapshots(&self, sources: &[SourceId]) -> Result<Vec<KanjiSnapshot>> {
+    use schema::kanji_snapshot::dsl::*;
+    kanji_snapshot
+      .filter(source_id.eq_any(sources))
+      .order(id.asc())
+      .select(KanjiSnapshot::as_select())
+      .load(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  /// Returns the kanji counts of the sources' snapshots, optionally only those of some kanji.
+  pub fn get_kanji_snapshot_kanjis(
+    &self,
+    sources: &[SourceId],
+    kanjis: Option<&[KanjiChar]>,
+  ) -> Result<Vec<KanjiSnapshotKanji>> {
+    use schema::kanji_snapshot_kanji::dsl::*;
+    let snapshots = schema::kanji_snapshot::table
+      .filter(schema::kanji_snapshot::source_id.eq_any(sources))
+      .select(schema::kanji_snapshot::id);
+
+    let mut query = kanji_snapshot_kanji
+      .filter(snapshot_id.eq_any(snapshots))
+      .select(KanjiSnapshotKanji::as_select())
+      .into_boxed();
+
+    if let Some(kanjis) = kanjis {
+      query = query.filter(kanji.eq_any(kanjis));
+    }
+
+    query
+      .load(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  /// Returns the oldest snapshot of the sources in which each kanji appears.
+  pub fn get_kanji_first_snapshots(
+    &self,
+    sources: &[SourceId],
+  ) -> Result<Vec<(KanjiChar, KanjiSnapshotId)>> {
+    use schema::kanji_snapshot_kanji::dsl::*;
+    let snapshots = schema::kanji_snapshot::table
+      .filter(schema::kanji_snapshot::source_id.eq_any(sources))
+      .select(schema::kanji_snapshot::id);
+
+    let first = kanji_snapshot_kanji
+      .filter(snapshot_id.eq_any(snapshots))
+      .group_by(kanji)
+      .select((kanji, min(snapshot_id)))
+      .load::<(KanjiChar, Option<KanjiSnapshotId>)>(&mut *self.conn())?
+      .into_iter()
+      .filter_map(|(character, snapshot)| Some((character, snapshot?)))
+      .collect();
+
+    Ok(first)
+  }
+}
+
+pub(super) fn delete_kanji_snapshots(
+  conn: &mut SqliteConnection,
+  snapshots: &[KanjiSnapshotId],
+) -> QueryResult<()> {
+  diesel::delete(
+    schema::kanji_snapshot_kanji::table
+      .filter(schema::kanji_snapshot_kanji::snapshot_id.eq_any(snapshots)),
+  )
+  .execute(conn)?;
+
+  diesel::delete(schema::kanji_snapshot::table.filter(schema::kanji_snapshot::id.eq_any(snapshots)))
+    .execute(conn)
+    .map(drop)
+}
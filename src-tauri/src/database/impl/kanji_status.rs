@@ -0,0 +1,58 @@
+use crate::database::model::kanji_status::KanjiStatusEntry;
+use crate::database::sql_types::{KanjiChar, KanjiStatus, Zoned};
+use crate::database::{DatabaseHandle, schema};
+use anyhow::Result;
+use diesel::prelude::*;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+impl DatabaseHandle {
+  pub fn get_kanji_status_entries(&self) -> Result<Vec<KanjiStatusEntry>> {
+    use schema::kanji_status::dsl::*;
+    kanji_status
+      .select(KanjiStatusEntry::as_select())
+      .load(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  pub fn get_kanji_statuses(&self) -> Result<HashMap<KanjiChar, KanjiStatus>> {
+    let statuses = self
+      .get_kanji_status_entries()?
+      .into_iter()
+      .map(|entry| (entry.id, entry.status))
+      .collect();
+
+    Ok(statuses)
+  }
+
+  /// Sets the status of every kanji, where being unknown is the same as having no status.
+  pub fn set_kanji_statuses(&self, kanjis: &[KanjiChar], new_status: KanjiStatus) -> Result<usize> {
+    use schema::kanji_status::dsl::*;
+    self.conn().transaction(|conn| {
+      if new_status == KanjiStatus::Unknown {
+        return diesel::delete(kanji_status.filter(id.eq_any(kanjis)))
+          .execute(conn)
+          .map_err(Into::into);
+      }
+
+      let now = Zoned::now();
+      let mut rows = 0;
+      for kanji in kanjis.iter().unique() {
+        let entry = KanjiStatusEntry {
+          id: *kanji,
+          status: new_status,
+          updated_at: now.clone(),
+        };
+
+        rows += diesel::insert_into(kanji_status)
+          .values(&entry)
+          .on_conflict(id)
+          .do_update()
+          .set((status.eq(new_status), updated_at.eq(&now)))
+          .execute(conn)?;
+      }
+
+      Ok(rows)
+    })
+  }
+}
@@ -1,5 +1,13 @@
 mod bookmark;
 mod kanji;
+mod kanji_dictionary;
+mod kanji_review;
+mod kanji_snapshot;
+mod kanji_status;
 mod quiz;
+mod quiz_session;
+mod reference_kanji;
 mod source;
+mod source_file;
 mod source_group;
+mod source_snippet;
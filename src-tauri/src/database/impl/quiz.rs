@@ -3,7 +3,9 @@ use crate::database::model::quiz_chunk_history::{NewQuizChunkHistoryEntry, QuizC
 use crate::database::sql_types::{KanjiChar, QuizAnswerId, SourceId};
 use crate::database::{DatabaseHandle, schema};
 use anyhow::Result;
+use diesel::dsl::sql;
 use diesel::prelude::*;
+use diesel::sql_types::{Double, Nullable};
 
 impl DatabaseHandle {
   pub fn clear_quiz_chunk_history(&self) -> Result<usize> {
@@ -99,6 +101,16 @@ impl DatabaseHandle {
       .map_err(Into::into)
   }
 
+  /// Returns how long the correct answers took on average, leaving out those that were not timed.
+  pub fn get_average_correct_quiz_duration(&self) -> Result<Option<f64>> {
+    use schema::quiz_answer::dsl::*;
+    quiz_answer
+      .filter(question.eq(answer))
+      .select(sql::<Nullable<Double>>("avg(duration)"))
+      .first(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
   pub fn get_quiz_answers(&self) -> Result<Vec<QuizAnswer>> {
     use schema::quiz_answer::dsl::*;
     quiz_answer
@@ -0,0 +1,94 @@
+use crate::database::model::quiz_answer::QuizAnswer;
+use crate::database::model::quiz_session::{NewQuizSession, QuizSession};
+use crate::database::sql_types::{QuizSessionId, Zoned};
+use crate::database::{DatabaseHandle, schema};
+use anyhow::Result;
+use diesel::dsl::{count, sql};
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+
+impl DatabaseHandle {
+  /// Counts the questions of the session that have at least one answer.
+  pub fn count_quiz_session_answered(&self, session: QuizSessionId) -> Result<i64> {
+    use schema::quiz_answer::dsl::*;
+    quiz_answer
+      .filter(session_id.eq(session))
+      .select(count(question).aggregate_distinct())
+      .get_result(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  pub fn count_quiz_session_questions(&self, session: QuizSessionId) -> Result<i64> {
+    use schema::quiz_session::dsl::*;
+    quiz_session
+      .find(session)
+      .select(sql::<BigInt>("json_array_length(questions)"))
+      .get_result(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  pub fn create_quiz_session(&self, new: &NewQuizSession) -> Result<QuizSessionId> {
+    use schema::quiz_session::dsl::*;
+    diesel::insert_into(quiz_session)
+      .values(new)
+      .returning(id)
+      .get_result(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  /// Marks the session as finished, unless it already was.
+  pub fn finish_quiz_session(&self, session: QuizSessionId) -> Result<usize> {
+    use schema::quiz_session::dsl::*;
+    diesel::update(quiz_session.find(session))
+      .filter(finished_at.is_null())
+      .set(finished_at.eq(Zoned::now()))
+      .execute(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  pub fn get_quiz_session(&self, session: QuizSessionId) -> Result<QuizSession> {
+    use schema::quiz_session::dsl::*;
+    quiz_session
+      .find(session)
+      .select(QuizSession::as_select())
+      .first(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  pub fn get_quiz_session_answers(&self, session: QuizSessionId) -> Result<Vec<QuizAnswer>> {
+    use schema::quiz_answer::dsl::*;
+    quiz_answer
+      .filter(session_id.eq(session))
+      .select(QuizAnswer::as_select())
+      .order(id.asc())
+      .load(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  /// Returns the most recently started session that is not finished yet.
+  pub fn get_last_unfinished_quiz_session(&self) -> Result<Option<QuizSession>> {
+    use schema::quiz_session::dsl::*;
+    quiz_session
+      .filter(finished_at.is_null())
+      .order(id.desc())
+      .select(QuizSession::as_select())
+      .first(&mut *self.conn())
+      .optional()
+      .map_err(Into::into)
+  }
+
+  /// Removes the session, keeping its answers, which no longer belong to any session.
+  pub fn remove_quiz_session(&self, session: QuizSessionId) -> Result<usize> {
+    use schema::quiz_session::dsl::*;
+    self.conn().transaction(|conn| {
+      diesel::update(schema::quiz_answer::table)
+        .filter(schema::quiz_answer::session_id.eq(session))
+        .set(schema::quiz_answer::session_id.eq(None::<QuizSessionId>))
+        .execute(conn)?;
+
+      diesel::delete(quiz_session.find(session))
+        .execute(conn)
+        .map_err(Into::into)
+    })
+  }
+}
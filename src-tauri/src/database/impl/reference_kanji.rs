@@ -0,0 +1,31 @@
+use crate::database::model::reference_kanji::ReferenceKanji;
+use crate::database::{DatabaseHandle, schema};
+use anyhow::Result;
+use diesel::prelude::*;
+
+impl DatabaseHandle {
+  pub fn get_reference_kanjis(&self) -> Result<Vec<ReferenceKanji>> {
+    use schema::reference_kanji::dsl::*;
+    reference_kanji
+      .select(ReferenceKanji::as_select())
+      .load(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  /// Replaces the whole reference frequency list with the given kanji.
+  pub fn replace_reference_kanjis(&self, kanjis: &[ReferenceKanji]) -> Result<usize> {
+    use schema::reference_kanji::dsl::*;
+    self.conn().transaction(|conn| {
+      diesel::delete(reference_kanji).execute(conn)?;
+
+      let mut rows = 0;
+      for chunk in kanjis.chunks(1000) {
+        rows += diesel::insert_into(reference_kanji)
+          .values(chunk)
+          .execute(conn)?;
+      }
+
+      Ok(rows)
+    })
+  }
+}
@@ -1,5 +1,6 @@
+use super::kanji_snapshot::delete_kanji_snapshots;
 use crate::database::model::source::{NewSource, Source};
-use crate::database::sql_types::{SourceId, SourceWeight, Zoned};
+use crate::database::sql_types::{GlobList, SourceId, SourceWeight, Zoned};
 use crate::database::{DatabaseHandle, schema};
 use anyhow::Result;
 use diesel::prelude::*;
@@ -62,9 +63,19 @@ impl DatabaseHandle {
 
   pub fn remove_source(&self, source_id: SourceId) -> Result<usize> {
     use schema::source::dsl::*;
-    diesel::delete(source.find(source_id))
-      .execute(&mut *self.conn())
-      .map_err(Into::into)
+    self.conn().transaction(|conn| {
+      clear_source_index(conn, source_id)?;
+
+      let snapshots = schema::kanji_snapshot::table
+        .filter(schema::kanji_snapshot::source_id.eq(source_id))
+        .select(schema::kanji_snapshot::id)
+        .load(conn)?;
+
+      delete_kanji_snapshots(conn, &snapshots)?;
+      diesel::delete(source.find(source_id))
+        .execute(conn)
+        .map_err(Into::into)
+    })
   }
 
   pub fn rename_source(&self, source_id: SourceId, new_name: &str) -> Result<()> {
@@ -76,6 +87,71 @@ impl DatabaseHandle {
       .map_err(Into::into)
   }
 
+  /// Changes the encoding of the source, clearing its index so every file is read again.
+  pub fn set_source_encoding(&self, source_id: SourceId, new_encoding: Option<&str>) -> Result<()> {
+    use schema::source::dsl::*;
+    self.conn().transaction(|conn| {
+      clear_source_index(conn, source_id)?;
+      diesel::update(source.find(source_id))
+        .set((encoding.eq(new_encoding), updated_at.eq(Zoned::now())))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+    })
+  }
+
+  pub fn set_source_globs(
+    &self,
+    source_id: SourceId,
+    new_include: &GlobList,
+    new_exclude: &GlobList,
+  ) -> Result<()> {
+    use schema::source::dsl::*;
+    diesel::update(source.find(source_id))
+      .set((
+        include.eq(new_include),
+        exclude.eq(new_exclude),
+        updated_at.eq(Zoned::now()),
+      ))
+      .execute(&mut *self.conn())
+      .map(drop)
+      .map_err(Into::into)
+  }
+
+  /// Changes whether Markdown headings are indexed, clearing the index so every file is read again.
+  pub fn set_source_include_headings(&self, source_id: SourceId, is_included: bool) -> Result<()> {
+    use schema::source::dsl::*;
+    self.conn().transaction(|conn| {
+      clear_source_index(conn, source_id)?;
+      diesel::update(source.find(source_id))
+        .set((
+          include_headings.eq(is_included),
+          updated_at.eq(Zoned::now()),
+        ))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+    })
+  }
+
+  pub fn set_source_max_depth(&self, source_id: SourceId, new_depth: Option<i32>) -> Result<()> {
+    use schema::source::dsl::*;
+    diesel::update(source.find(source_id))
+      .set((max_depth.eq(new_depth), updated_at.eq(Zoned::now())))
+      .execute(&mut *self.conn())
+      .map(drop)
+      .map_err(Into::into)
+  }
+
+  pub fn set_source_respect_gitignore(&self, source_id: SourceId, respect: bool) -> Result<()> {
+    use schema::source::dsl::*;
+    diesel::update(source.find(source_id))
+      .set((respect_gitignore.eq(respect), updated_at.eq(Zoned::now())))
+      .execute(&mut *self.conn())
+      .map(drop)
+      .map_err(Into::into)
+  }
+
   pub fn set_source_weight(&self, source_id: SourceId, new_weight: SourceWeight) -> Result<()> {
     use schema::source::dsl::*;
     diesel::update(source.find(source_id))
@@ -94,3 +170,24 @@ impl DatabaseHandle {
       .map_err(Into::into)
   }
 }
+
+fn clear_source_index(conn: &mut SqliteConnection, source_id: SourceId) -> QueryResult<()> {
+  diesel::delete(
+    schema::source_file_kanji::table.filter(schema::source_file_kanji::source_id.eq(source_id)),
+  )
+  .execute(conn)?;
+
+  diesel::delete(
+    schema::source_file_word::table.filter(schema::source_file_word::source_id.eq(source_id)),
+  )
+  .execute(conn)?;
+
+  diesel::delete(
+    schema::source_snippet::table.filter(schema::source_snippet::source_id.eq(source_id)),
+  )
+  .execute(conn)?;
+
+  diesel::delete(schema::source_file::table.filter(schema::source_file::source_id.eq(source_id)))
+    .execute(conn)
+    .map(drop)
+}
@@ -0,0 +1,248 @@
+use crate::core::scan::{ScannedFile, ScannedSnippet};
+use crate::core::snippet::SnippetLocation;
+use crate::database::model::source_file::{
+  NewSourceFile,
+  NewSourceFileKanji,
+  NewSourceFileWord,
+  SourceFile,
+};
+use crate::database::model::source_snippet::NewSourceSnippet;
+use crate::database::sql_types::{KanjiChar, SourceFileId, SourceId};
+use crate::database::{DatabaseHandle, schema};
+use anyhow::Result;
+use diesel::dsl::sum;
+use diesel::prelude::*;
+use itertools::Itertools;
+
+impl DatabaseHandle {
+  /// Inserts or updates the file, replacing all its kanji counts and snippets.
+  pub fn create_source_file(
+    &self,
+    new: &NewSourceFile,
+    scanned: &ScannedFile,
+  ) -> Result<SourceFileId> {
+    use schema::source_file::dsl::*;
+    self.conn().transaction(|conn| {
+      let file_id = diesel::insert_into(source_file)
+        .values(new)
+        .on_conflict((source_id, path))
+        .do_update()
+        .set((
+          size.eq(new.size),
+          modified_at.eq(new.modified_at),
+          scanned_at.eq(&new.scanned_at),
+          warning.eq(&new.warning),
+          tokenizer.eq(&new.tokenizer),
+        ))
+        .returning(id)
+        .get_result::<SourceFileId>(conn)?;
+
+      diesel::delete(
+        schema::source_file_kanji::table
+          .filter(schema::source_file_kanji::source_file_id.eq(file_id)),
+      )
+      .execute(conn)?;
+
+      diesel::delete(
+        schema::source_file_word::table
+          .filter(schema::source_file_word::source_file_id.eq(file_id)),
+      )
+      .execute(conn)?;
+
+      diesel::delete(
+        schema::source_snippet::table.filter(schema::source_snippet::source_file_id.eq(file_id)),
+      )
+      .execute(conn)?;
+
+      let rows = scanned
+        .kanjis
+        .iter()
+        .map(|(kanji, seen)| {
+          NewSourceFileKanji {
+            source_file_id: file_id,
+            source_id: new.source_id,
+            kanji: *kanji,
+            seen: i32::try_from(*seen).unwrap_or(i32::MAX),
+          }
+        })
+        .collect_vec();
+
+      for chunk in rows.chunks(1000) {
+        diesel::insert_into(schema::source_file_kanji::table)
+          .values(chunk)
+          .execute(conn)?;
+      }
+
+      let rows = scanned
+        .words
+        .iter()
+        .map(|(word, seen)| {
+          NewSourceFileWord {
+            source_file_id: file_id,
+            source_id: new.source_id,
+            word,
+            seen: i32::try_from(*seen).unwrap_or(i32::MAX),
+          }
+        })
+        .collect_vec();
+
+      for chunk in rows.chunks(1000) {
+        diesel::insert_into(schema::source_file_word::table)
+          .values(chunk)
+          .execute(conn)?;
+      }
+
+      insert_snippets(conn, file_id, new.source_id, &scanned.snippets)?;
+
+      Ok(file_id)
+    })
+  }
+
+  /// Sums the kanji counts of every indexed file, grouped by source.
+  pub fn get_kanji_counts(&self, sources: &[SourceId]) -> Result<Vec<(KanjiChar, SourceId, u64)>> {
+    use schema::source_file_kanji::dsl::*;
+    let counts = source_file_kanji
+      .filter(source_id.eq_any(sources))
+      .group_by((kanji, source_id))
+      .select((kanji, source_id, sum(seen)))
+      .load::<(KanjiChar, SourceId, Option<i64>)>(&mut *self.conn())?
+      .into_iter()
+      .map(|(character, source, count)| {
+        let count = count.and_then(|it| u64::try_from(it).ok());
+        (character, source, count.unwrap_or(0))
+      })
+      .collect();
+
+    Ok(counts)
+  }
+
+  /// Sums the word counts of every indexed file, grouped by source and by whether they were
+  /// found by a tokenizer.
+  pub fn get_word_counts(
+    &self,
+    sources: &[SourceId],
+  ) -> Result<Vec<(String, SourceId, bool, u64)>> {
+    use schema::source_file_word::dsl::*;
+    let mut counts = Vec::new();
+    for tokenized in [false, true] {
+      let rows = source_file_word
+        .inner_join(schema::source_file::table)
+        .filter(source_id.eq_any(sources))
+        .filter(
+          schema::source_file::tokenizer
+            .is_not_null()
+            .eq(tokenized),
+        )
+        .group_by((word, source_id))
+        .select((word, source_id, sum(seen)))
+        .load::<(String, SourceId, Option<i64>)>(&mut *self.conn())?;
+
+      counts.extend(
+        rows
+          .into_iter()
+          .map(|(text, source, count)| {
+            let count = count.and_then(|it| u64::try_from(it).ok());
+            (text, source, tokenized, count.unwrap_or(0))
+          }),
+      );
+    }
+
+    Ok(counts)
+  }
+
+  pub fn get_source_files(&self, source: SourceId) -> Result<Vec<SourceFile>> {
+    use schema::source_file::dsl::*;
+    source_file
+      .filter(source_id.eq(source))
+      .select(SourceFile::as_select())
+      .load(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  pub fn get_source_file_warnings(&self, source: SourceId) -> Result<Vec<SourceFile>> {
+    use schema::source_file::dsl::*;
+    source_file
+      .filter(source_id.eq(source))
+      .filter(warning.is_not_null())
+      .select(SourceFile::as_select())
+      .order(path.asc())
+      .load(&mut *self.conn())
+      .map_err(Into::into)
+  }
+
+  pub fn remove_source_files(&self, ids: &[SourceFileId]) -> Result<usize> {
+    use schema::source_file::dsl::*;
+    self.conn().transaction(|conn| {
+      diesel::delete(
+        schema::source_file_kanji::table
+          .filter(schema::source_file_kanji::source_file_id.eq_any(ids)),
+      )
+      .execute(conn)?;
+
+      diesel::delete(
+        schema::source_file_word::table
+          .filter(schema::source_file_word::source_file_id.eq_any(ids)),
+      )
+      .execute(conn)?;
+
+      diesel::delete(
+        schema::source_snippet::table.filter(schema::source_snippet::source_file_id.eq_any(ids)),
+      )
+      .execute(conn)?;
+
+      diesel::delete(source_file.filter(id.eq_any(ids)))
+        .execute(conn)
+        .map_err(Into::into)
+    })
+  }
+}
+
+fn insert_snippets(
+  conn: &mut SqliteConnection,
+  file_id: SourceFileId,
+  source_id: SourceId,
+  snippets: &[ScannedSnippet],
+) -> Result<()> {
+  let rows = snippets
+    .iter()
+    .map(|snippet| {
+      let (chapter, line, cue) = match snippet.location {
+        SnippetLocation::Line { line } => (None, line, None),
+        SnippetLocation::Chapter { chapter, position } => (Some(chapter), position, None),
+        SnippetLocation::Cue { line, start, end } => (None, line, Some((start, end))),
+      };
+
+      let to_i32 = |value: usize| i32::try_from(value).unwrap_or(i32::MAX);
+      let to_i64 = |value: u64| i64::try_from(value).unwrap_or(i64::MAX);
+
+      let furigana = (!snippet.furigana.is_empty())
+        .then(|| serde_json::to_string(&snippet.furigana))
+        .transpose()?;
+
+      let tokens = (!snippet.tokens.is_empty())
+        .then(|| serde_json::to_string(&snippet.tokens))
+        .transpose()?;
+
+      Ok::<_, serde_json::Error>(NewSourceSnippet {
+        source_file_id: file_id,
+        source_id,
+        chapter: chapter.map(to_i32),
+        line: to_i32(line),
+        cue_start: cue.map(|(start, _)| to_i64(start)),
+        cue_end: cue.map(|(_, end)| to_i64(end)),
+        content: &snippet.content,
+        kanji: &snippet.kanjis,
+        furigana,
+        tokens,
+      })
+    })
+    .try_collect::<_, Vec<_>, _>()?;
+
+  for chunk in rows.chunks(500) {
+    diesel::insert_into(schema::source_snippet::table)
+      .values(chunk)
+      .execute(conn)?;
+  }
+
+  Ok(())
+}
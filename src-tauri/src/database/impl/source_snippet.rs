@@ -0,0 +1,55 @@
+use crate::database::DatabaseHandle;
+use crate::database::model::source_snippet::SourceSnippetMatch;
+use crate::database::sql_types::{KanjiChar, SourceId};
+use anyhow::Result;
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::{Integer, Text};
+use itertools::Itertools;
+
+impl DatabaseHandle {
+  pub fn search_source_snippets(
+    &self,
+    source: SourceId,
+    words: &[String],
+  ) -> Result<Vec<SourceSnippetMatch>> {
+    // Only the kanji are indexed, so a paragraph matches a word if it has all of its kanji.
+    // Whether the word itself appears in there is checked afterwards.
+    let query = words
+      .iter()
+      .map(|word| {
+        word
+          .chars()
+          .filter_map(KanjiChar::from_char)
+          .unique()
+          .map(|kanji| format!("\"{kanji}\""))
+          .join(" AND ")
+      })
+      .filter(|group| !group.is_empty())
+      .map(|group| format!("({group})"))
+      .join(" OR ");
+
+    sql_query(
+      "
+      SELECT
+        source_file.path,
+        source_snippet.chapter,
+        source_snippet.line,
+        source_snippet.cue_start,
+        source_snippet.cue_end,
+        source_snippet.content,
+        source_snippet.furigana,
+        source_snippet.tokens
+      FROM source_snippet_fts
+      INNER JOIN source_snippet ON source_snippet.id = source_snippet_fts.rowid
+      INNER JOIN source_file ON source_file.id = source_snippet.source_file_id
+      WHERE source_snippet_fts MATCH ? AND source_snippet.source_id = ?
+      ORDER BY source_file.path, source_snippet.chapter, source_snippet.line
+      ",
+    )
+    .bind::<Text, _>(query)
+    .bind::<Integer, _>(source)
+    .load(&mut *self.conn())
+    .map_err(Into::into)
+  }
+}
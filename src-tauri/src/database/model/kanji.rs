@@ -26,7 +26,7 @@ pub struct NewKanji {
   created_at: Zoned,
 
   #[builder(default)]
-  updated_at: Zoned,
+  pub updated_at: Zoned,
 }
 
 #[cfg(desktop)]
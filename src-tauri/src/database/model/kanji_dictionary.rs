@@ -0,0 +1,29 @@
+use crate::database::sql_types::{KanjiChar, StringList};
+use diesel::prelude::*;
+use serde::Serialize;
+
+/// Entry of the kanji dictionary, imported from a KANJIDIC2 file.
+#[derive(Identifiable, Queryable, Selectable, Insertable, Clone, Debug, Serialize)]
+#[diesel(table_name = crate::database::schema::kanji_dictionary)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all = "camelCase")]
+pub struct KanjiDictionaryEntry {
+  pub id: KanjiChar,
+  pub on_readings: StringList,
+  pub kun_readings: StringList,
+
+  /// English meanings.
+  pub meanings: StringList,
+
+  /// School grade in which the kanji is taught, where 8 means the rest of the Jōyō kanji
+  /// and 9 or 10 mean the Jinmeiyō kanji.
+  pub grade: Option<i32>,
+
+  /// Level of the former JLPT, from 4 to 1.
+  pub jlpt: Option<i32>,
+
+  pub stroke_count: Option<i32>,
+
+  /// Rank among the 2500 most used kanji in newspapers.
+  pub frequency: Option<i32>,
+}
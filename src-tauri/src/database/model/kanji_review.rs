@@ -0,0 +1,24 @@
+use crate::database::sql_types::{KanjiChar, Zoned};
+use diesel::prelude::*;
+use serde::Serialize;
+
+#[derive(Identifiable, Queryable, Selectable, Insertable, AsChangeset, Clone, Debug, Serialize)]
+#[diesel(table_name = crate::database::schema::kanji_review)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all = "camelCase")]
+pub struct KanjiReview {
+  pub id: KanjiChar,
+  pub ease: f64,
+
+  /// Days between the last review and the next one.
+  pub interval: f64,
+
+  /// Reviews passed in a row since the kanji was first seen or last forgotten.
+  pub repetitions: i32,
+
+  /// Times the kanji was forgotten after having been passed at least once.
+  pub lapses: i32,
+
+  pub due_at: Zoned,
+  pub reviewed_at: Zoned,
+}
@@ -0,0 +1,30 @@
+use crate::database::sql_types::{KanjiChar, KanjiSnapshotId, SourceId, Zoned};
+use diesel::prelude::*;
+use serde::Serialize;
+
+/// Kanji counts of a source, as they were after one of its scans.
+#[derive(Identifiable, Queryable, Selectable, Clone, Debug, Serialize)]
+#[diesel(table_name = crate::database::schema::kanji_snapshot)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all = "camelCase")]
+pub struct KanjiSnapshot {
+  pub id: KanjiSnapshotId,
+  pub source_id: SourceId,
+  pub created_at: Zoned,
+}
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = crate::database::schema::kanji_snapshot)]
+pub struct NewKanjiSnapshot {
+  pub source_id: SourceId,
+  pub created_at: Zoned,
+}
+
+#[derive(Queryable, Selectable, Insertable, Clone, Debug)]
+#[diesel(table_name = crate::database::schema::kanji_snapshot_kanji)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct KanjiSnapshotKanji {
+  pub snapshot_id: KanjiSnapshotId,
+  pub kanji: KanjiChar,
+  pub seen: i64,
+}
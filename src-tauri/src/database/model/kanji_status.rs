@@ -0,0 +1,13 @@
+use crate::database::sql_types::{KanjiChar, KanjiStatus, Zoned};
+use diesel::prelude::*;
+use serde::Serialize;
+
+#[derive(Identifiable, Queryable, Selectable, Insertable, Clone, Debug, Serialize)]
+#[diesel(table_name = crate::database::schema::kanji_status)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all = "camelCase")]
+pub struct KanjiStatusEntry {
+  pub id: KanjiChar,
+  pub status: KanjiStatus,
+  pub updated_at: Zoned,
+}
@@ -1,6 +1,14 @@
 pub mod bookmark;
 pub mod kanji;
+pub mod kanji_dictionary;
+pub mod kanji_review;
+pub mod kanji_snapshot;
+pub mod kanji_status;
 pub mod quiz_answer;
 pub mod quiz_chunk_history;
+pub mod quiz_session;
+pub mod reference_kanji;
 pub mod source;
+pub mod source_file;
 pub mod source_group;
+pub mod source_snippet;
@@ -1,4 +1,11 @@
-use crate::database::sql_types::{KanjiChar, QuizAnswerId, SourceId, Zoned};
+use crate::database::sql_types::{
+  KanjiChar,
+  QuizAnswerId,
+  QuizConfidence,
+  QuizSessionId,
+  SourceId,
+  Zoned,
+};
 use bon::Builder;
 use diesel::prelude::*;
 use serde::Serialize;
@@ -16,6 +23,11 @@ pub struct QuizAnswer {
   pub answer: KanjiChar,
   pub created_at: Zoned,
   pub source_id: Option<SourceId>,
+
+  /// Time taken to answer, in milliseconds.
+  pub duration: Option<i64>,
+  pub confidence: Option<QuizConfidence>,
+  pub session_id: Option<QuizSessionId>,
 }
 
 #[derive(Insertable, Builder, Clone, Debug)]
@@ -35,6 +47,13 @@ pub struct NewQuizAnswer {
 
   #[builder(into)]
   source_id: Option<SourceId>,
+
+  /// Time taken to answer, in milliseconds.
+  duration: Option<i64>,
+
+  confidence: Option<QuizConfidence>,
+
+  session_id: Option<QuizSessionId>,
 }
 
 #[cfg(desktop)]
@@ -0,0 +1,39 @@
+use crate::database::sql_types::{QuizSessionId, Zoned};
+use bon::Builder;
+use diesel::prelude::*;
+
+#[cfg(desktop)]
+use {crate::manager::ManagerExt, anyhow::Result, tauri::AppHandle};
+
+#[derive(Identifiable, Queryable, Selectable, Clone, Debug)]
+#[diesel(table_name = crate::database::schema::quiz_session)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct QuizSession {
+  pub id: QuizSessionId,
+
+  /// Kind of the quiz, as JSON.
+  pub kind: String,
+
+  /// Questions of the quiz, as JSON.
+  pub questions: String,
+
+  pub started_at: Zoned,
+  pub finished_at: Option<Zoned>,
+}
+
+#[derive(Insertable, Builder, Clone, Debug)]
+#[diesel(table_name = crate::database::schema::quiz_session)]
+pub struct NewQuizSession {
+  kind: String,
+  questions: String,
+
+  #[builder(skip = Zoned::now())]
+  started_at: Zoned,
+}
+
+#[cfg(desktop)]
+impl NewQuizSession {
+  pub fn create(self, app: &AppHandle) -> Result<QuizSessionId> {
+    app.database().create_quiz_session(&self)
+  }
+}
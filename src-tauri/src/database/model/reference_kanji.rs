@@ -0,0 +1,11 @@
+use crate::database::sql_types::KanjiChar;
+use diesel::prelude::*;
+
+/// How often a kanji is seen in the reference frequency list.
+#[derive(Identifiable, Queryable, Selectable, Insertable, Clone, Debug)]
+#[diesel(table_name = crate::database::schema::reference_kanji)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ReferenceKanji {
+  pub id: KanjiChar,
+  pub seen: i64,
+}
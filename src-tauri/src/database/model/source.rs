@@ -1,4 +1,4 @@
-use crate::database::sql_types::{SourceId, SourceWeight, SqlPath, Zoned};
+use crate::database::sql_types::{GlobList, SourceId, SourceWeight, SqlPath, Zoned};
 use bon::Builder;
 use diesel::prelude::*;
 use serde::Serialize;
@@ -18,6 +18,24 @@ pub struct Source {
   pub updated_at: Zoned,
   pub enabled: bool,
   pub weight: SourceWeight,
+
+  /// How deep to walk into subfolders, where `None` means no limit.
+  pub max_depth: Option<i32>,
+
+  /// Files must match one of these globs, relative to the source folder.
+  pub include: GlobList,
+
+  /// Files matching any of these globs are skipped, even if included.
+  pub exclude: GlobList,
+
+  /// Whether to honor `.gitignore` files and skip hidden entries.
+  pub respect_gitignore: bool,
+
+  /// Encoding of the files, where `None` means it is detected for each file.
+  pub encoding: Option<String>,
+
+  /// Whether the headings of Markdown files are indexed along with their prose.
+  pub include_headings: bool,
 }
 
 #[derive(Insertable, Builder, Clone, Debug)]
@@ -37,6 +55,9 @@ pub struct NewSource<'a> {
 
   #[builder(skip)]
   weight: SourceWeight,
+
+  #[builder(skip = GlobList::source_files())]
+  include: GlobList,
 }
 
 #[cfg(desktop)]
@@ -0,0 +1,76 @@
+use crate::database::sql_types::{KanjiChar, SourceFileId, SourceId, SqlPath, Zoned};
+use bon::Builder;
+use diesel::prelude::*;
+use serde::Serialize;
+
+#[cfg(desktop)]
+use {
+  crate::core::scan::ScannedFile,
+  crate::manager::ManagerExt,
+  anyhow::Result,
+  tauri::AppHandle,
+};
+
+#[derive(Identifiable, Queryable, Selectable, Clone, Debug, Serialize)]
+#[diesel(table_name = crate::database::schema::source_file)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all = "camelCase")]
+pub struct SourceFile {
+  pub id: SourceFileId,
+  pub source_id: SourceId,
+  pub path: SqlPath,
+  pub size: i64,
+  pub modified_at: i64,
+  pub scanned_at: Zoned,
+  pub warning: Option<String>,
+  pub tokenizer: Option<String>,
+}
+
+#[derive(Insertable, Builder, Clone, Debug)]
+#[diesel(table_name = crate::database::schema::source_file)]
+pub struct NewSourceFile {
+  #[builder(start_fn)]
+  pub source_id: SourceId,
+
+  #[builder(start_fn, into)]
+  pub path: SqlPath,
+
+  pub size: i64,
+  pub modified_at: i64,
+
+  #[builder(skip = Zoned::now())]
+  pub scanned_at: Zoned,
+
+  /// Why the file could not be read, if that was the case.
+  pub warning: Option<String>,
+
+  /// Dictionary with which the snippets of the file were split into words, if any.
+  pub tokenizer: Option<String>,
+}
+
+#[cfg(desktop)]
+impl NewSourceFile {
+  pub fn create(self, app: &AppHandle, scanned: &ScannedFile) -> Result<SourceFileId> {
+    app
+      .database()
+      .create_source_file(&self, scanned)
+  }
+}
+
+#[derive(Insertable, Clone, Copy, Debug)]
+#[diesel(table_name = crate::database::schema::source_file_kanji)]
+pub struct NewSourceFileKanji {
+  pub source_file_id: SourceFileId,
+  pub source_id: SourceId,
+  pub kanji: KanjiChar,
+  pub seen: i32,
+}
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = crate::database::schema::source_file_word)]
+pub struct NewSourceFileWord<'a> {
+  pub source_file_id: SourceFileId,
+  pub source_id: SourceId,
+  pub word: &'a str,
+  pub seen: i32,
+}
@@ -0,0 +1,43 @@
+use crate::database::sql_types::{SourceFileId, SourceId, SqlPath};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Nullable, Text};
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = crate::database::schema::source_snippet)]
+pub struct NewSourceSnippet<'a> {
+  pub source_file_id: SourceFileId,
+  pub source_id: SourceId,
+  pub chapter: Option<i32>,
+  pub line: i32,
+  pub cue_start: Option<i64>,
+  pub cue_end: Option<i64>,
+  pub content: &'a str,
+  pub kanji: &'a str,
+
+  /// Ruby readings of the content, as JSON.
+  pub furigana: Option<String>,
+
+  /// Words of the content found by the tokenizer, as JSON.
+  pub tokens: Option<String>,
+}
+
+#[derive(QueryableByName, Clone, Debug)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct SourceSnippetMatch {
+  #[diesel(sql_type = Text)]
+  pub path: SqlPath,
+  #[diesel(sql_type = Nullable<Integer>)]
+  pub chapter: Option<i32>,
+  #[diesel(sql_type = Integer)]
+  pub line: i32,
+  #[diesel(sql_type = Nullable<BigInt>)]
+  pub cue_start: Option<i64>,
+  #[diesel(sql_type = Nullable<BigInt>)]
+  pub cue_end: Option<i64>,
+  #[diesel(sql_type = Text)]
+  pub content: String,
+  #[diesel(sql_type = Nullable<Text>)]
+  pub furigana: Option<String>,
+  #[diesel(sql_type = Nullable<Text>)]
+  pub tokens: Option<String>,
+}
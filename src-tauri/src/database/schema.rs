@@ -58,6 +58,190 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Representation of the `kanji_dictionary` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    kanji_dictionary (id) {
+        /// The `id` column of the `kanji_dictionary` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Text,
+        /// The `on_readings` column of the `kanji_dictionary` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        on_readings -> Text,
+        /// The `kun_readings` column of the `kanji_dictionary` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kun_readings -> Text,
+        /// The `meanings` column of the `kanji_dictionary` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        meanings -> Text,
+        /// The `grade` column of the `kanji_dictionary` table.
+        ///
+        /// Its SQL type is `Nullable<Integer>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        grade -> Nullable<Integer>,
+        /// The `jlpt` column of the `kanji_dictionary` table.
+        ///
+        /// Its SQL type is `Nullable<Integer>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        jlpt -> Nullable<Integer>,
+        /// The `stroke_count` column of the `kanji_dictionary` table.
+        ///
+        /// Its SQL type is `Nullable<Integer>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        stroke_count -> Nullable<Integer>,
+        /// The `frequency` column of the `kanji_dictionary` table.
+        ///
+        /// Its SQL type is `Nullable<Integer>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        frequency -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `kanji_review` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    kanji_review (id) {
+        /// The `id` column of the `kanji_review` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Text,
+        /// The `ease` column of the `kanji_review` table.
+        ///
+        /// Its SQL type is `Double`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ease -> Double,
+        /// The `interval` column of the `kanji_review` table.
+        ///
+        /// Its SQL type is `Double`.
+        ///
+        /// (Automatically generated by Diesel.)
+        interval -> Double,
+        /// The `repetitions` column of the `kanji_review` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        repetitions -> Integer,
+        /// The `lapses` column of the `kanji_review` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        lapses -> Integer,
+        /// The `due_at` column of the `kanji_review` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        due_at -> Text,
+        /// The `reviewed_at` column of the `kanji_review` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        reviewed_at -> Text,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `kanji_snapshot` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    kanji_snapshot (id) {
+        /// The `id` column of the `kanji_snapshot` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Integer,
+        /// The `source_id` column of the `kanji_snapshot` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_id -> Integer,
+        /// The `created_at` column of the `kanji_snapshot` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Text,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `kanji_snapshot_kanji` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    kanji_snapshot_kanji (snapshot_id, kanji) {
+        /// The `snapshot_id` column of the `kanji_snapshot_kanji` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        snapshot_id -> Integer,
+        /// The `kanji` column of the `kanji_snapshot_kanji` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kanji -> Text,
+        /// The `seen` column of the `kanji_snapshot_kanji` table.
+        ///
+        /// Its SQL type is `BigInt`.
+        ///
+        /// (Automatically generated by Diesel.)
+        seen -> BigInt,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `kanji_status` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    kanji_status (id) {
+        /// The `id` column of the `kanji_status` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Text,
+        /// The `status` column of the `kanji_status` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        status -> Text,
+        /// The `updated_at` column of the `kanji_status` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Text,
+    }
+}
+
 diesel::table! {
     /// Representation of the `quiz_answer` table.
     ///
@@ -93,6 +277,24 @@ diesel::table! {
         ///
         /// (Automatically generated by Diesel.)
         source_id -> Nullable<Integer>,
+        /// The `duration` column of the `quiz_answer` table.
+        ///
+        /// Its SQL type is `Nullable<BigInt>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        duration -> Nullable<BigInt>,
+        /// The `confidence` column of the `quiz_answer` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        confidence -> Nullable<Text>,
+        /// The `session_id` column of the `quiz_answer` table.
+        ///
+        /// Its SQL type is `Nullable<Integer>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        session_id -> Nullable<Integer>,
     }
 }
 
@@ -122,6 +324,64 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Representation of the `quiz_session` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    quiz_session (id) {
+        /// The `id` column of the `quiz_session` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Integer,
+        /// The `kind` column of the `quiz_session` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kind -> Text,
+        /// The `questions` column of the `quiz_session` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        questions -> Text,
+        /// The `started_at` column of the `quiz_session` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        started_at -> Text,
+        /// The `finished_at` column of the `quiz_session` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        finished_at -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `reference_kanji` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    reference_kanji (id) {
+        /// The `id` column of the `reference_kanji` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Text,
+        /// The `seen` column of the `reference_kanji` table.
+        ///
+        /// Its SQL type is `BigInt`.
+        ///
+        /// (Automatically generated by Diesel.)
+        seen -> BigInt,
+    }
+}
+
 diesel::table! {
     /// Representation of the `source` table.
     ///
@@ -169,6 +429,162 @@ diesel::table! {
         ///
         /// (Automatically generated by Diesel.)
         weight -> Integer,
+        /// The `max_depth` column of the `source` table.
+        ///
+        /// Its SQL type is `Nullable<Integer>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        max_depth -> Nullable<Integer>,
+        /// The `include` column of the `source` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        include -> Text,
+        /// The `exclude` column of the `source` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        exclude -> Text,
+        /// The `respect_gitignore` column of the `source` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        respect_gitignore -> Bool,
+        /// The `encoding` column of the `source` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        encoding -> Nullable<Text>,
+        /// The `include_headings` column of the `source` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        include_headings -> Bool,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `source_file` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    source_file (id) {
+        /// The `id` column of the `source_file` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Integer,
+        /// The `source_id` column of the `source_file` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_id -> Integer,
+        /// The `path` column of the `source_file` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        path -> Text,
+        /// The `size` column of the `source_file` table.
+        ///
+        /// Its SQL type is `BigInt`.
+        ///
+        /// (Automatically generated by Diesel.)
+        size -> BigInt,
+        /// The `modified_at` column of the `source_file` table.
+        ///
+        /// Its SQL type is `BigInt`.
+        ///
+        /// (Automatically generated by Diesel.)
+        modified_at -> BigInt,
+        /// The `scanned_at` column of the `source_file` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        scanned_at -> Text,
+        /// The `warning` column of the `source_file` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        warning -> Nullable<Text>,
+        /// The `tokenizer` column of the `source_file` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        tokenizer -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `source_file_kanji` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    source_file_kanji (source_file_id, kanji) {
+        /// The `source_file_id` column of the `source_file_kanji` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_file_id -> Integer,
+        /// The `source_id` column of the `source_file_kanji` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_id -> Integer,
+        /// The `kanji` column of the `source_file_kanji` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kanji -> Text,
+        /// The `seen` column of the `source_file_kanji` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        seen -> Integer,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `source_file_word` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    source_file_word (source_file_id, word) {
+        /// The `source_file_id` column of the `source_file_word` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_file_id -> Integer,
+        /// The `source_id` column of the `source_file_word` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_id -> Integer,
+        /// The `word` column of the `source_file_word` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        word -> Text,
+        /// The `seen` column of the `source_file_word` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        seen -> Integer,
     }
 }
 
@@ -224,17 +640,111 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Representation of the `source_snippet` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    source_snippet (id) {
+        /// The `id` column of the `source_snippet` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Integer,
+        /// The `source_file_id` column of the `source_snippet` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_file_id -> Integer,
+        /// The `source_id` column of the `source_snippet` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_id -> Integer,
+        /// The `line` column of the `source_snippet` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        line -> Integer,
+        /// The `content` column of the `source_snippet` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        content -> Text,
+        /// The `kanji` column of the `source_snippet` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kanji -> Text,
+        /// The `chapter` column of the `source_snippet` table.
+        ///
+        /// Its SQL type is `Nullable<Integer>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        chapter -> Nullable<Integer>,
+        /// The `cue_start` column of the `source_snippet` table.
+        ///
+        /// Its SQL type is `Nullable<BigInt>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        cue_start -> Nullable<BigInt>,
+        /// The `cue_end` column of the `source_snippet` table.
+        ///
+        /// Its SQL type is `Nullable<BigInt>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        cue_end -> Nullable<BigInt>,
+        /// The `furigana` column of the `source_snippet` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        furigana -> Nullable<Text>,
+        /// The `tokens` column of the `source_snippet` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        tokens -> Nullable<Text>,
+    }
+}
+
 diesel::joinable!(bookmark -> source (source_id));
+diesel::joinable!(kanji_snapshot -> source (source_id));
+diesel::joinable!(kanji_snapshot_kanji -> kanji_snapshot (snapshot_id));
 diesel::joinable!(quiz_answer -> source (source_id));
+diesel::joinable!(source_file -> source (source_id));
+diesel::joinable!(source_file_kanji -> source (source_id));
+diesel::joinable!(source_file_kanji -> source_file (source_file_id));
+diesel::joinable!(source_file_word -> source (source_id));
+diesel::joinable!(source_file_word -> source_file (source_file_id));
 diesel::joinable!(source_group_source -> source (source_id));
 diesel::joinable!(source_group_source -> source_group (source_group_id));
+diesel::joinable!(source_snippet -> source (source_id));
+diesel::joinable!(source_snippet -> source_file (source_file_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
   bookmark,
   kanji,
+  kanji_dictionary,
+  kanji_review,
+  kanji_snapshot,
+  kanji_snapshot_kanji,
+  kanji_status,
   quiz_answer,
   quiz_chunk_history,
+  quiz_session,
+  reference_kanji,
   source,
+  source_file,
+  source_file_kanji,
+  source_file_word,
   source_group,
   source_group_source,
+  source_snippet,
 );
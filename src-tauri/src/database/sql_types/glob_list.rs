@@ -0,0 +1,43 @@
+use derive_more::Deref;
+use diesel::backend::Backend;
+use diesel::deserialize::{self as de, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::serialize::{self as ser, IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::Sqlite;
+use serde::{Deserialize, Serialize};
+
+/// List of glob patterns, stored as a JSON array.
+#[derive(FromSqlRow, AsExpression, Clone, Debug, Default, Deref, Deserialize, Serialize)]
+#[diesel(sql_type = Text)]
+pub struct GlobList(Vec<String>);
+
+impl GlobList {
+  /// Globs matching every kind of file a source can read.
+  pub fn source_files() -> Self {
+    Self(
+      [
+        "*.md", "*.txt", "*.epub", "*.srt", "*.ass", "*.ssa", "*.vtt",
+      ]
+      .map(ToOwned::to_owned)
+      .to_vec(),
+    )
+  }
+}
+
+impl FromSql<Text, Sqlite> for GlobList {
+  fn from_sql(bytes: <Sqlite as Backend>::RawValue<'_>) -> de::Result<Self> {
+    let value = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+    Ok(GlobList(serde_json::from_str(&value)?))
+  }
+}
+
+impl ToSql<Text, Sqlite> for GlobList
+where
+  String: ToSql<Text, Sqlite>,
+{
+  fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> ser::Result {
+    out.set_value(serde_json::to_string(&self.0)?);
+    Ok(IsNull::No)
+  }
+}
@@ -67,4 +67,12 @@ macro_rules! impl_u32 {
   }
 }
 
-impl_u32!(BookmarkId, KanjiSetChunkId, SourceId, SourceGroupId);
+impl_u32!(
+  BookmarkId,
+  KanjiSetChunkId,
+  KanjiSnapshotId,
+  QuizSessionId,
+  SourceFileId,
+  SourceGroupId,
+  SourceId
+);
@@ -1,4 +1,4 @@
-use crate::core::kanji::is_kanji;
+use crate::core::kanji::{is_iteration_mark, is_kanji};
 use anyhow::{Result, anyhow};
 use derive_more::{Deref, Display};
 use diesel::backend::Backend;
@@ -29,8 +29,10 @@ use serde::{Deserialize, Serialize};
 pub struct KanjiChar(char);
 
 impl KanjiChar {
+  /// Iteration marks are accepted so that they can always be indexed,
+  /// leaving it to the settings whether they are counted.
   pub fn from_char(value: char) -> Option<Self> {
-    is_kanji(value).then_some(Self(value))
+    (is_kanji(value) || is_iteration_mark(value)).then_some(Self(value))
   }
 }
 
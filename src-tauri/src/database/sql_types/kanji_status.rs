@@ -0,0 +1,60 @@
+use diesel::backend::Backend;
+use diesel::deserialize::{self as de, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::serialize::{self as ser, IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::Sqlite;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use strum::{AsRefStr, EnumString};
+
+/// How well a kanji is known, where kanji without a status are unknown.
+#[derive(
+  FromSqlRow,
+  AsExpression,
+  AsRefStr,
+  EnumString,
+  Clone,
+  Copy,
+  Debug,
+  Default,
+  PartialEq,
+  Eq,
+  Hash,
+  Deserialize,
+  Serialize,
+)]
+#[diesel(sql_type = Text)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum KanjiStatus {
+  #[default]
+  Unknown,
+  Learning,
+  Known,
+  Ignored,
+}
+
+impl KanjiStatus {
+  /// Whether the kanji should be left out of sets and quizzes.
+  pub fn is_skipped(self) -> bool {
+    matches!(self, Self::Known | Self::Ignored)
+  }
+}
+
+impl FromSql<Text, Sqlite> for KanjiStatus {
+  fn from_sql(bytes: <Sqlite as Backend>::RawValue<'_>) -> de::Result<Self> {
+    let value = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+    Ok(KanjiStatus::from_str(value.as_str())?)
+  }
+}
+
+impl ToSql<Text, Sqlite> for KanjiStatus
+where
+  str: ToSql<Text, Sqlite>,
+{
+  fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> ser::Result {
+    out.set_value(self.as_ref());
+    Ok(IsNull::No)
+  }
+}
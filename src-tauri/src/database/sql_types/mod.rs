@@ -1,15 +1,31 @@
+mod glob_list;
 mod id;
 mod kanji_char;
+mod kanji_status;
 mod path;
+mod quiz_confidence;
 mod source_weight;
+mod string_list;
 mod uuid;
 mod version;
 mod zoned;
 
-pub use id::{BookmarkId, KanjiSetChunkId, SourceGroupId, SourceId};
+pub use glob_list::GlobList;
+pub use id::{
+  BookmarkId,
+  KanjiSetChunkId,
+  KanjiSnapshotId,
+  QuizSessionId,
+  SourceFileId,
+  SourceGroupId,
+  SourceId,
+};
 pub use kanji_char::KanjiChar;
+pub use kanji_status::KanjiStatus;
 pub use path::SqlPath;
+pub use quiz_confidence::QuizConfidence;
 pub use source_weight::SourceWeight;
+pub use string_list::StringList;
 pub use uuid::QuizAnswerId;
 pub use version::Version;
 pub use zoned::Zoned;
@@ -0,0 +1,50 @@
+use diesel::backend::Backend;
+use diesel::deserialize::{self as de, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::serialize::{self as ser, IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::Sqlite;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use strum::{AsRefStr, EnumString};
+
+/// How sure one was of a quiz answer, as rated right after giving it.
+#[derive(
+  FromSqlRow,
+  AsExpression,
+  AsRefStr,
+  EnumString,
+  Clone,
+  Copy,
+  Debug,
+  PartialEq,
+  Eq,
+  Hash,
+  Deserialize,
+  Serialize,
+)]
+#[diesel(sql_type = Text)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum QuizConfidence {
+  Low,
+  Medium,
+  High,
+}
+
+impl FromSql<Text, Sqlite> for QuizConfidence {
+  fn from_sql(bytes: <Sqlite as Backend>::RawValue<'_>) -> de::Result<Self> {
+    let value = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+    Ok(QuizConfidence::from_str(value.as_str())?)
+  }
+}
+
+impl ToSql<Text, Sqlite> for QuizConfidence
+where
+  str: ToSql<Text, Sqlite>,
+{
+  fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> ser::Result {
+    out.set_value(self.as_ref());
+    Ok(IsNull::No)
+  }
+}
@@ -0,0 +1,30 @@
+use derive_more::{Deref, From};
+use diesel::backend::Backend;
+use diesel::deserialize::{self as de, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::serialize::{self as ser, IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::Sqlite;
+use serde::{Deserialize, Serialize};
+
+/// List of strings, stored as a JSON array.
+#[derive(FromSqlRow, AsExpression, Clone, Debug, Default, Deref, From, Deserialize, Serialize)]
+#[diesel(sql_type = Text)]
+pub struct StringList(Vec<String>);
+
+impl FromSql<Text, Sqlite> for StringList {
+  fn from_sql(bytes: <Sqlite as Backend>::RawValue<'_>) -> de::Result<Self> {
+    let value = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+    Ok(StringList(serde_json::from_str(&value)?))
+  }
+}
+
+impl ToSql<Text, Sqlite> for StringList
+where
+  String: ToSql<Text, Sqlite>,
+{
+  fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> ser::Result {
+    out.set_value(serde_json::to_string(&self.0)?);
+    Ok(IsNull::No)
+  }
+}
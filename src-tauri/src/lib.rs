@@ -1,5 +1,5 @@
 #![cfg_attr(mobile, expect(unused))]
-#![feature(file_buffered, nonpoison_mutex, sync_nonpoison, try_blocks)]
+#![feature(nonpoison_mutex, sync_nonpoison, try_blocks)]
 
 mod database;
 mod error;
@@ -18,6 +18,8 @@ mod http;
 mod server;
 #[cfg(desktop)]
 mod tray;
+#[cfg(desktop)]
+mod watcher;
 
 use crate::manager::PathResolverExt;
 use error::BoxResult;
@@ -41,21 +43,47 @@ pub fn run() {
       command::bookmark::get_bookmarks,
       command::bookmark::remove_bookmark,
       command::kanji::export_set,
+      command::kanji::get_frequency_divergences,
+      command::kanji::get_kanji_detail,
+      command::kanji::get_kanji_history,
+      command::kanji::get_kanji_statuses,
+      command::kanji::get_new_kanji,
       command::kanji::get_set,
+      command::kanji::import_kanji_dictionary,
+      command::kanji::import_kanji_statuses,
+      command::kanji::import_reference_frequencies,
       command::kanji::search_kanji,
       command::kanji::search_snippets,
+      command::kanji::search_word_snippets,
+      command::kanji::search_words,
+      command::kanji::set_kanji_status,
+      command::kanji::tokenize_text,
       command::quiz::clear_quiz_chunk_history,
       command::quiz::create_quiz,
       command::quiz::create_quiz_answer,
       command::quiz::create_quiz_chunk_history_entry,
+      command::quiz::create_quiz_session,
+      command::quiz::finish_quiz_session,
+      command::quiz::get_kanji_reviews,
       command::quiz::get_quiz_answers,
       command::quiz::get_quiz_chunk_history_entries,
+      command::quiz::get_quiz_confusions,
+      command::quiz::get_quiz_kanji_stats,
+      command::quiz::get_quiz_session_summary,
       command::quiz::get_quiz_source_stats,
+      command::quiz::remove_quiz_session,
+      command::quiz::resume_quiz_session,
       command::source::create_source,
       command::source::get_source,
+      command::source::get_source_warnings,
       command::source::get_sources,
       command::source::rename_source,
       command::source::remove_source,
+      command::source::set_source_encoding,
+      command::source::set_source_globs,
+      command::source::set_source_include_headings,
+      command::source::set_source_max_depth,
+      command::source::set_source_respect_gitignore,
       command::source::set_source_weight,
       command::source::toggle_source,
       command::source_group::create_source_group,
@@ -66,7 +94,8 @@ pub fn run() {
       command::source_group::get_source_groups,
       command::source_group::remove_source_group,
       command::source_group::rename_source_group,
-      command::source_group::set_source_group_sources
+      command::source_group::set_source_group_sources,
+      command::task::cancel_task
     ]);
 
   #[cfg(mobile)]
@@ -90,10 +119,14 @@ pub fn run() {
 fn setup(app: &AppHandle) -> BoxResult<()> {
   #[cfg(desktop)]
   {
+    use core::task::Tasks;
     use database::DatabaseHandle;
     use server::Server;
+    use watcher::SourceWatcher;
 
     app.manage(DatabaseHandle::new(app)?);
+    app.manage(Tasks::default());
+    app.manage(SourceWatcher::new(app)?);
     app.manage(Server::serve(app)?);
   }
 
@@ -6,8 +6,10 @@ use tauri::path::PathResolver;
 
 #[cfg(desktop)]
 use {
+  crate::core::task::Tasks,
   crate::database::DatabaseHandle,
   crate::server::Server,
+  crate::watcher::SourceWatcher,
   tauri::{Manager, State},
 };
 
@@ -20,6 +22,14 @@ pub trait ManagerExt: Manager<Wry> {
   fn server(&self) -> State<'_, Server> {
     self.app_handle().state::<Server>()
   }
+
+  fn tasks(&self) -> State<'_, Tasks> {
+    self.app_handle().state::<Tasks>()
+  }
+
+  fn watcher(&self) -> State<'_, SourceWatcher> {
+    self.app_handle().state::<SourceWatcher>()
+  }
 }
 
 #[cfg(desktop)]
@@ -1,6 +1,7 @@
 mod model;
 mod response;
 mod router;
+mod stream;
 
 use anyhow::Result;
 use local_ip_address::local_ip;
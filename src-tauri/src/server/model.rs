@@ -1,15 +1,28 @@
 use crate::core::quiz::QuizKind;
 use crate::core::snippet::Snippet;
+use crate::core::task::TaskId;
 use crate::database::sql_types::{
   BookmarkId,
+  GlobList,
   KanjiChar,
   KanjiSetChunkId,
+  KanjiStatus,
+  QuizConfidence,
+  QuizSessionId,
   SourceGroupId,
   SourceId,
   SourceWeight,
   SqlPath,
 };
+use crate::settings::DistractorStrategy;
 use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelTaskRequest {
+  pub id: TaskId,
+}
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -21,6 +34,10 @@ pub struct CreateBookmarkRequest {
 #[serde(rename_all = "camelCase")]
 pub struct CreateQuizRequest {
   pub kind: QuizKind,
+
+  /// Ignored by quizzes of confused kanji, whose options always come from past confusions.
+  pub strategy: Option<DistractorStrategy>,
+  pub task: Option<TaskId>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,6 +46,9 @@ pub struct CreateQuizAnswerRequest {
   pub question: KanjiChar,
   pub answer: KanjiChar,
   pub source: Option<SourceId>,
+  pub duration: Option<u64>,
+  pub confidence: Option<QuizConfidence>,
+  pub session: Option<QuizSessionId>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -49,6 +69,49 @@ pub struct CreateSourceGroupRequest {
   pub name: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinishQuizSessionRequest {
+  pub id: QuizSessionId,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFrequencyDivergencesRequest {
+  pub limit: Option<usize>,
+  pub task: Option<TaskId>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetKanjiDetailRequest {
+  pub kanji: KanjiChar,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetKanjiHistoryRequest {
+  pub kanji: Option<KanjiChar>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNewKanjiRequest {
+  pub days: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetQuizConfusionsRequest {
+  pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetQuizSessionSummaryRequest {
+  pub id: QuizSessionId,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetSourceRequest {
@@ -73,12 +136,43 @@ pub struct GetSourceGroupSourcesRequest {
   pub id: SourceGroupId,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSourceWarningsRequest {
+  pub id: SourceId,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportKanjiDictionaryRequest {
+  pub path: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportKanjiStatusesRequest {
+  pub path: PathBuf,
+  pub status: Option<KanjiStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReferenceFrequenciesRequest {
+  pub path: PathBuf,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoveBookmarkRequest {
   pub id: BookmarkId,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveQuizSessionRequest {
+  pub id: QuizSessionId,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoveSourceRequest {
@@ -105,6 +199,12 @@ pub struct RenameSourceGroupRequest {
   pub name: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeQuizSessionRequest {
+  pub id: Option<QuizSessionId>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchSnippetsRequest {
@@ -112,6 +212,20 @@ pub struct SearchSnippetsRequest {
   pub source: Option<SourceId>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchWordSnippetsRequest {
+  pub word: String,
+  pub source: Option<SourceId>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetKanjiStatusRequest {
+  pub kanjis: Vec<KanjiChar>,
+  pub status: KanjiStatus,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SetSourceGroupSourcesRequest {
@@ -119,6 +233,42 @@ pub struct SetSourceGroupSourcesRequest {
   pub sources: Vec<SourceId>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSourceEncodingRequest {
+  pub id: SourceId,
+  pub encoding: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSourceGlobsRequest {
+  pub id: SourceId,
+  pub include: GlobList,
+  pub exclude: GlobList,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSourceIncludeHeadingsRequest {
+  pub id: SourceId,
+  pub include: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSourceMaxDepthRequest {
+  pub id: SourceId,
+  pub depth: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSourceRespectGitignoreRequest {
+  pub id: SourceId,
+  pub respect: bool,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SetSourceWeightRequest {
@@ -126,9 +276,21 @@ pub struct SetSourceWeightRequest {
   pub weight: SourceWeight,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskRequest {
+  pub task: Option<TaskId>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ToggleSourceRequest {
   pub id: SourceId,
   pub enabled: bool,
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenizeTextRequest {
+  pub text: String,
+}
@@ -1,11 +1,20 @@
 use super::model::*;
-use crate::{command, res};
+use super::stream::ndjson;
+use crate::command::task::OnProgress;
+use crate::core::kanji_set::KanjiSet;
+use crate::core::quiz::{Quiz, QuizSessionState};
+use crate::core::{kanji, word};
+use crate::manager::ManagerExt;
+use crate::watcher::SOURCE_CHANGED_EVENT;
+use crate::{command, res, settings};
 use axum::Router;
 use axum::extract::{Json, State};
-use axum::response::Response;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
-use futures::TryFutureExt;
+use futures::{FutureExt, TryFutureExt, stream};
 use tauri::AppHandle;
+use tokio::sync::broadcast::error::RecvError;
 use tower_http::cors::{Any, CorsLayer};
 
 #[rustfmt::skip]
@@ -16,38 +25,77 @@ pub(super) fn create() -> Router<AppHandle> {
     .allow_origin(Any);
 
   Router::new()
+    .route("/cancel-task", post(cancel_task))
     .route("/clear-quiz-chunk-history", get(clear_quiz_chunk_history))
     .route("/create-bookmark", post(create_bookmark))
     .route("/create-quiz", post(create_quiz))
     .route("/create-quiz-answer", post(create_quiz_answer))
     .route("/create-quiz-chunk-history-entry", post(create_quiz_chunk_history_entry))
+    .route("/create-quiz-session", post(create_quiz_session))
+    .route("/create-quiz-session-stream", post(create_quiz_session_stream))
+    .route("/create-quiz-stream", post(create_quiz_stream))
     .route("/create-source", post(create_source))
     .route("/create-source-group", post(create_source_group))
+    .route("/events", get(events))
+    .route("/finish-quiz-session", post(finish_quiz_session))
     .route("/get-bookmarks", get(get_bookmarks))
+    .route("/get-frequency-divergences", post(get_frequency_divergences))
+    .route("/get-kanji-detail", post(get_kanji_detail))
+    .route("/get-kanji-history", post(get_kanji_history))
+    .route("/get-kanji-reviews", get(get_kanji_reviews))
+    .route("/get-kanji-statuses", get(get_kanji_statuses))
+    .route("/get-new-kanji", post(get_new_kanji))
     .route("/get-quiz-answers", get(get_quiz_answers))
     .route("/get-quiz-chunk-history-entries", get(get_quiz_chunk_history_entries))
+    .route("/get-quiz-confusions", post(get_quiz_confusions))
+    .route("/get-quiz-kanji-stats", get(get_quiz_kanji_stats))
+    .route("/get-quiz-session-summary", post(get_quiz_session_summary))
     .route("/get-quiz-source-stats", get(get_quiz_source_stats))
     .route("/get-set", get(get_set))
+    .route("/get-set-stream", post(get_set_stream))
     .route("/get-source", post(get_source))
     .route("/get-source-group", post(get_source_group))
     .route("/get-source-group-ids", get(get_source_group_ids))
     .route("/get-source-group-source-ids", post(get_source_group_source_ids))
     .route("/get-source-group-sources", post(get_source_group_sources))
     .route("/get-source-groups", get(get_source_groups))
+    .route("/get-source-warnings", post(get_source_warnings))
     .route("/get-sources", get(get_sources))
+    .route("/import-kanji-dictionary", post(import_kanji_dictionary))
+    .route("/import-kanji-statuses", post(import_kanji_statuses))
+    .route("/import-reference-frequencies", post(import_reference_frequencies))
     .route("/remove-bookmark", post(remove_bookmark))
+    .route("/remove-quiz-session", post(remove_quiz_session))
     .route("/remove-source", post(remove_source))
     .route("/remove-source-group", post(remove_source_group))
     .route("/rename-source", post(rename_source))
     .route("/rename-source-group", post(rename_source_group))
+    .route("/resume-quiz-session", post(resume_quiz_session))
     .route("/search-kanji", get(search_kanji))
+    .route("/search-kanji-stream", post(search_kanji_stream))
     .route("/search-snippets", post(search_snippets))
+    .route("/search-word-snippets", post(search_word_snippets))
+    .route("/search-words", get(search_words))
+    .route("/search-words-stream", post(search_words_stream))
+    .route("/set-kanji-status", post(set_kanji_status))
     .route("/set-source-group-sources", post(set_source_group_sources))
+    .route("/set-source-encoding", post(set_source_encoding))
+    .route("/set-source-globs", post(set_source_globs))
+    .route("/set-source-include-headings", post(set_source_include_headings))
+    .route("/set-source-max-depth", post(set_source_max_depth))
+    .route("/set-source-respect-gitignore", post(set_source_respect_gitignore))
     .route("/set-source-weight", post(set_source_weight))
     .route("/toggle-source", post(toggle_source))
+    .route("/tokenize-text", post(tokenize_text))
     .layer(cors)
 }
 
+async fn cancel_task(State(app): State<AppHandle>, Json(req): Json<CancelTaskRequest>) -> Response {
+  command::task::cancel_task(app, req.id)
+    .map(|cancelled| res!(OK, Json(cancelled)))
+    .await
+}
+
 async fn clear_quiz_chunk_history(State(app): State<AppHandle>) -> Response {
   command::quiz::clear_quiz_chunk_history(app)
     .map_ok(|rows| res!(OK, Json(rows)))
@@ -66,20 +114,41 @@ async fn create_bookmark(
 }
 
 async fn create_quiz(State(app): State<AppHandle>, Json(req): Json<CreateQuizRequest>) -> Response {
-  command::quiz::create_quiz(app, req.kind)
+  command::quiz::create_quiz(app, req.kind, req.strategy, req.task, OnProgress::default())
     .map_ok(|quiz| res!(CREATED, Json(quiz)))
     .unwrap_or_else(Response::from)
     .await
 }
 
+async fn create_quiz_stream(
+  State(app): State<AppHandle>,
+  Json(req): Json<CreateQuizRequest>,
+) -> Response {
+  ndjson(app, req.task, |app, task| {
+    let strategy = req
+      .strategy
+      .unwrap_or_else(|| settings::distractor_strategy(&app));
+
+    Quiz::new(app, req.kind, strategy, task).map_err(Into::into)
+  })
+}
+
 async fn create_quiz_answer(
   State(app): State<AppHandle>,
   Json(req): Json<CreateQuizAnswerRequest>,
 ) -> Response {
-  command::quiz::create_quiz_answer(app, req.question, req.answer, req.source)
-    .map_ok(|id| res!(CREATED, Json(id)))
-    .unwrap_or_else(Response::from)
-    .await
+  command::quiz::create_quiz_answer(
+    app,
+    req.question,
+    req.answer,
+    req.source,
+    req.duration,
+    req.confidence,
+    req.session,
+  )
+  .map_ok(|id| res!(CREATED, Json(id)))
+  .unwrap_or_else(Response::from)
+  .await
 }
 
 async fn create_quiz_chunk_history_entry(
@@ -92,6 +161,29 @@ async fn create_quiz_chunk_history_entry(
     .await
 }
 
+async fn create_quiz_session(
+  State(app): State<AppHandle>,
+  Json(req): Json<CreateQuizRequest>,
+) -> Response {
+  command::quiz::create_quiz_session(app, req.kind, req.strategy, req.task, OnProgress::default())
+    .map_ok(|session| res!(CREATED, Json(session)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn create_quiz_session_stream(
+  State(app): State<AppHandle>,
+  Json(req): Json<CreateQuizRequest>,
+) -> Response {
+  ndjson(app, req.task, |app, task| {
+    let strategy = req
+      .strategy
+      .unwrap_or_else(|| settings::distractor_strategy(&app));
+
+    QuizSessionState::start(app, req.kind, strategy, task).map_err(Into::into)
+  })
+}
+
 async fn create_source(
   State(app): State<AppHandle>,
   Json(req): Json<CreateSourceRequest>,
@@ -112,6 +204,41 @@ async fn create_source_group(
     .await
 }
 
+async fn events(State(app): State<AppHandle>) -> Response {
+  let receiver = app.watcher().subscribe();
+  let stream = stream::unfold(receiver, |mut receiver| {
+    async move {
+      loop {
+        match receiver.recv().await {
+          Ok(changed) => {
+            let event = Event::default()
+              .event(SOURCE_CHANGED_EVENT)
+              .json_data(changed);
+
+            return Some((event, receiver));
+          }
+          Err(RecvError::Lagged(_)) => {}
+          Err(RecvError::Closed) => return None,
+        }
+      }
+    }
+  });
+
+  Sse::new(stream)
+    .keep_alive(KeepAlive::default())
+    .into_response()
+}
+
+async fn finish_quiz_session(
+  State(app): State<AppHandle>,
+  Json(req): Json<FinishQuizSessionRequest>,
+) -> Response {
+  command::quiz::finish_quiz_session(app, req.id)
+    .map_ok(|summary| res!(OK, Json(summary)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
 async fn get_bookmarks(State(app): State<AppHandle>) -> Response {
   command::bookmark::get_bookmarks(app)
     .map_ok(|bookmarks| res!(OK, Json(bookmarks)))
@@ -119,6 +246,60 @@ async fn get_bookmarks(State(app): State<AppHandle>) -> Response {
     .await
 }
 
+async fn get_frequency_divergences(
+  State(app): State<AppHandle>,
+  Json(req): Json<GetFrequencyDivergencesRequest>,
+) -> Response {
+  command::kanji::get_frequency_divergences(app, req.limit, req.task, OnProgress::default())
+    .map_ok(|divergences| res!(OK, Json(divergences)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn get_kanji_detail(
+  State(app): State<AppHandle>,
+  Json(req): Json<GetKanjiDetailRequest>,
+) -> Response {
+  command::kanji::get_kanji_detail(app, req.kanji)
+    .map_ok(|detail| res!(OK, Json(detail)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn get_kanji_history(
+  State(app): State<AppHandle>,
+  Json(req): Json<GetKanjiHistoryRequest>,
+) -> Response {
+  command::kanji::get_kanji_history(app, req.kanji)
+    .map_ok(|history| res!(OK, Json(history)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn get_kanji_reviews(State(app): State<AppHandle>) -> Response {
+  command::quiz::get_kanji_reviews(app)
+    .map_ok(|reviews| res!(OK, Json(reviews)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn get_kanji_statuses(State(app): State<AppHandle>) -> Response {
+  command::kanji::get_kanji_statuses(app)
+    .map_ok(|statuses| res!(OK, Json(statuses)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn get_new_kanji(
+  State(app): State<AppHandle>,
+  Json(req): Json<GetNewKanjiRequest>,
+) -> Response {
+  command::kanji::get_new_kanji(app, req.days)
+    .map_ok(|kanjis| res!(OK, Json(kanjis)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
 async fn get_quiz_answers(State(app): State<AppHandle>) -> Response {
   command::quiz::get_quiz_answers(app)
     .map_ok(|answers| res!(OK, Json(answers)))
@@ -133,6 +314,33 @@ async fn get_quiz_chunk_history_entries(State(app): State<AppHandle>) -> Respons
     .await
 }
 
+async fn get_quiz_confusions(
+  State(app): State<AppHandle>,
+  Json(req): Json<GetQuizConfusionsRequest>,
+) -> Response {
+  command::quiz::get_quiz_confusions(app, req.limit)
+    .map_ok(|confusions| res!(OK, Json(confusions)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn get_quiz_kanji_stats(State(app): State<AppHandle>) -> Response {
+  command::quiz::get_quiz_kanji_stats(app)
+    .map_ok(|stats| res!(OK, Json(stats)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn get_quiz_session_summary(
+  State(app): State<AppHandle>,
+  Json(req): Json<GetQuizSessionSummaryRequest>,
+) -> Response {
+  command::quiz::get_quiz_session_summary(app, req.id)
+    .map_ok(|summary| res!(OK, Json(summary)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
 async fn get_quiz_source_stats(State(app): State<AppHandle>) -> Response {
   command::quiz::get_quiz_source_stats(app)
     .map_ok(|stats| res!(OK, Json(stats)))
@@ -141,12 +349,18 @@ async fn get_quiz_source_stats(State(app): State<AppHandle>) -> Response {
 }
 
 async fn get_set(State(app): State<AppHandle>) -> Response {
-  command::kanji::get_set(app)
+  command::kanji::get_set(app, None, OnProgress::default())
     .map_ok(|set| res!(OK, Json(set)))
     .unwrap_or_else(Response::from)
     .await
 }
 
+async fn get_set_stream(State(app): State<AppHandle>, Json(req): Json<TaskRequest>) -> Response {
+  ndjson(app, req.task, |app, task| {
+    async move { KanjiSet::load(&app, task).await }.map_err(Into::into)
+  })
+}
+
 async fn get_source(State(app): State<AppHandle>, Json(req): Json<GetSourceRequest>) -> Response {
   command::source::get_source(app, req.id)
     .map_ok(|source| res!(OK, Json(source)))
@@ -198,6 +412,16 @@ async fn get_source_groups(State(app): State<AppHandle>) -> Response {
     .await
 }
 
+async fn get_source_warnings(
+  State(app): State<AppHandle>,
+  Json(req): Json<GetSourceWarningsRequest>,
+) -> Response {
+  command::source::get_source_warnings(app, req.id)
+    .map_ok(|files| res!(OK, Json(files)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
 async fn get_sources(State(app): State<AppHandle>) -> Response {
   command::source::get_sources(app)
     .map_ok(|sources| res!(OK, Json(sources)))
@@ -205,6 +429,36 @@ async fn get_sources(State(app): State<AppHandle>) -> Response {
     .await
 }
 
+async fn import_kanji_dictionary(
+  State(app): State<AppHandle>,
+  Json(req): Json<ImportKanjiDictionaryRequest>,
+) -> Response {
+  command::kanji::import_kanji_dictionary(app, req.path)
+    .map_ok(|rows| res!(OK, Json(rows)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn import_kanji_statuses(
+  State(app): State<AppHandle>,
+  Json(req): Json<ImportKanjiStatusesRequest>,
+) -> Response {
+  command::kanji::import_kanji_statuses(app, req.path, req.status)
+    .map_ok(|rows| res!(OK, Json(rows)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn import_reference_frequencies(
+  State(app): State<AppHandle>,
+  Json(req): Json<ImportReferenceFrequenciesRequest>,
+) -> Response {
+  command::kanji::import_reference_frequencies(app, req.path)
+    .map_ok(|rows| res!(OK, Json(rows)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
 async fn remove_bookmark(
   State(app): State<AppHandle>,
   Json(req): Json<RemoveBookmarkRequest>,
@@ -215,6 +469,16 @@ async fn remove_bookmark(
     .await
 }
 
+async fn remove_quiz_session(
+  State(app): State<AppHandle>,
+  Json(req): Json<RemoveQuizSessionRequest>,
+) -> Response {
+  command::quiz::remove_quiz_session(app, req.id)
+    .map_ok(|rows| res!(OK, Json(rows)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
 async fn remove_source(
   State(app): State<AppHandle>,
   Json(req): Json<RemoveSourceRequest>,
@@ -255,13 +519,32 @@ async fn rename_source_group(
     .await
 }
 
+async fn resume_quiz_session(
+  State(app): State<AppHandle>,
+  Json(req): Json<ResumeQuizSessionRequest>,
+) -> Response {
+  command::quiz::resume_quiz_session(app, req.id)
+    .map_ok(|session| res!(OK, Json(session)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
 async fn search_kanji(State(app): State<AppHandle>) -> Response {
-  command::kanji::search_kanji(app)
+  command::kanji::search_kanji(app, None, OnProgress::default())
     .map_ok(|stats| res!(OK, Json(stats)))
     .unwrap_or_else(Response::from)
     .await
 }
 
+async fn search_kanji_stream(
+  State(app): State<AppHandle>,
+  Json(req): Json<TaskRequest>,
+) -> Response {
+  ndjson(app, req.task, |app, task| {
+    kanji::search(app, task).map_err(Into::into)
+  })
+}
+
 async fn search_snippets(
   State(app): State<AppHandle>,
   Json(req): Json<SearchSnippetsRequest>,
@@ -272,6 +555,42 @@ async fn search_snippets(
     .await
 }
 
+async fn search_word_snippets(
+  State(app): State<AppHandle>,
+  Json(req): Json<SearchWordSnippetsRequest>,
+) -> Response {
+  command::kanji::search_word_snippets(app, req.word, req.source)
+    .map_ok(|snippets| res!(OK, Json(snippets)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn search_words(State(app): State<AppHandle>) -> Response {
+  command::kanji::search_words(app, None, OnProgress::default())
+    .map_ok(|stats| res!(OK, Json(stats)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn search_words_stream(
+  State(app): State<AppHandle>,
+  Json(req): Json<TaskRequest>,
+) -> Response {
+  ndjson(app, req.task, |app, task| {
+    word::search(app, task).map_err(Into::into)
+  })
+}
+
+async fn set_kanji_status(
+  State(app): State<AppHandle>,
+  Json(req): Json<SetKanjiStatusRequest>,
+) -> Response {
+  command::kanji::set_kanji_status(app, req.kanjis, req.status)
+    .map_ok(|rows| res!(OK, Json(rows)))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
 async fn set_source_group_sources(
   State(app): State<AppHandle>,
   Json(req): Json<SetSourceGroupSourcesRequest>,
@@ -282,6 +601,56 @@ async fn set_source_group_sources(
     .await
 }
 
+async fn set_source_encoding(
+  State(app): State<AppHandle>,
+  Json(req): Json<SetSourceEncodingRequest>,
+) -> Response {
+  command::source::set_source_encoding(app, req.id, req.encoding)
+    .map_ok(|()| res!(OK))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn set_source_globs(
+  State(app): State<AppHandle>,
+  Json(req): Json<SetSourceGlobsRequest>,
+) -> Response {
+  command::source::set_source_globs(app, req.id, req.include, req.exclude)
+    .map_ok(|()| res!(OK))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn set_source_include_headings(
+  State(app): State<AppHandle>,
+  Json(req): Json<SetSourceIncludeHeadingsRequest>,
+) -> Response {
+  command::source::set_source_include_headings(app, req.id, req.include)
+    .map_ok(|()| res!(OK))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn set_source_max_depth(
+  State(app): State<AppHandle>,
+  Json(req): Json<SetSourceMaxDepthRequest>,
+) -> Response {
+  command::source::set_source_max_depth(app, req.id, req.depth)
+    .map_ok(|()| res!(OK))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
+async fn set_source_respect_gitignore(
+  State(app): State<AppHandle>,
+  Json(req): Json<SetSourceRespectGitignoreRequest>,
+) -> Response {
+  command::source::set_source_respect_gitignore(app, req.id, req.respect)
+    .map_ok(|()| res!(OK))
+    .unwrap_or_else(Response::from)
+    .await
+}
+
 async fn set_source_weight(
   State(app): State<AppHandle>,
   Json(req): Json<SetSourceWeightRequest>,
@@ -301,3 +670,13 @@ async fn toggle_source(
     .unwrap_or_else(Response::from)
     .await
 }
+
+async fn tokenize_text(
+  State(app): State<AppHandle>,
+  Json(req): Json<TokenizeTextRequest>,
+) -> Response {
+  command::kanji::tokenize_text(app, req.text)
+    .map_ok(|tokens| res!(OK, Json(tokens)))
+    .unwrap_or_else(Response::from)
+    .await
+}
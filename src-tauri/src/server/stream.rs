@@ -0,0 +1,73 @@
+use crate::core::task::{Task, TaskId, TaskProgress};
+use crate::error::CResult;
+use crate::manager::ManagerExt;
+use axum::body::Body;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::Response;
+use futures::stream;
+use serde::Serialize;
+use serde_json::Value;
+use std::convert::Infallible;
+use tauri::AppHandle;
+use tauri::async_runtime::spawn;
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+/// Runs the task in the background, streaming its progress as newline-delimited JSON.
+///
+/// The last line holds either the result or the error. If the client disconnects before
+/// that, the task is cancelled.
+pub(super) fn ndjson<F, Fut, T>(app: AppHandle, id: Option<TaskId>, f: F) -> Response
+where
+  F: FnOnce(AppHandle, Task) -> Fut,
+  Fut: Future<Output = CResult<T>> + Send + 'static,
+  T: Serialize,
+{
+  let (tx, rx) = mpsc::unbounded_channel();
+  let task = Task::with_progress(on_progress(tx.clone()));
+  let handle = app.tasks().register(id, task);
+  let future = f(app, handle.task());
+
+  spawn(async move {
+    let message = match future.await {
+      Ok(value) => {
+        serde_json::to_value(value)
+          .map(StreamMessage::Done)
+          .unwrap_or_else(|err| StreamMessage::Error(err.into()))
+      }
+      Err(err) => StreamMessage::Error(err),
+    };
+
+    let _ = tx.send(message);
+  });
+
+  let stream = stream::unfold(Some((rx, handle)), |state| {
+    async move {
+      let (mut rx, handle) = state?;
+      let message = rx.recv().await?;
+      let mut line = serde_json::to_vec(&message).ok()?;
+      line.push(b'\n');
+
+      let state = matches!(message, StreamMessage::Progress(_)).then_some((rx, handle));
+      Some((Ok::<_, Infallible>(line), state))
+    }
+  });
+
+  Response::builder()
+    .header(CONTENT_TYPE, "application/x-ndjson")
+    .body(Body::from_stream(stream))
+    .unwrap()
+}
+
+fn on_progress(tx: UnboundedSender<StreamMessage>) -> impl Fn(&TaskProgress) + Send + Sync {
+  move |progress| {
+    let _ = tx.send(StreamMessage::Progress(*progress));
+  }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", content = "data", rename_all = "kebab-case")]
+enum StreamMessage {
+  Progress(TaskProgress),
+  Done(Value),
+  Error(crate::error::Error),
+}
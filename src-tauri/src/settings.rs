@@ -1,17 +1,55 @@
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use strum::AsRefStr;
 use tauri::AppHandle;
 use tauri_plugin_pinia::ManagerExt as _;
 
+pub const DEFAULT_CONFUSION_LIMIT: usize = 50;
+pub const DEFAULT_DISTRACTOR_STRATEGY: DistractorStrategy = DistractorStrategy::Mixed;
+pub const DEFAULT_DIVERGENCE_LIMIT: usize = 100;
 pub const DEFAULT_EDITOR: Editor = Editor::Code;
 pub const DEFAULT_HIDE_ON_CLOSE: bool = false;
 pub const DEFAULT_IGNORE_SOURCE_WEIGHT: bool = false;
+pub const DEFAULT_ITERATION_MARKS: bool = false;
+pub const DEFAULT_NEW_KANJI_DAYS: u32 = 7;
+pub const DEFAULT_NORMALIZE_VARIANTS: bool = false;
 pub const DEFAULT_SET_CHUNK_SIZE: usize = 25;
 pub const DEFAULT_SET_FILE_NAME: &str = "Kanji Set.txt";
 pub const DEFAULT_SHUFFLE_SNIPPETS: bool = true;
+pub const DEFAULT_SLOW_ANSWER_RATIO: f64 = 1.5;
+pub const DEFAULT_SNIPPET_CONTEXT: usize = 1;
 pub const DEFAULT_SNIPPET_LIMIT: usize = 1000;
+pub const DEFAULT_SNIPPET_MAX_LEN: usize = 150;
 pub const DEFAULT_SNIPPET_MIN_LEN: usize = 5;
+pub const DEFAULT_WORD_OKURIGANA: bool = false;
+
+/// How the wrong options of a quiz question are chosen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DistractorStrategy {
+  /// Any kanji at all.
+  Random,
+
+  /// Kanji sharing radicals or other components with the answer, such as `清` for `晴`.
+  Components,
+
+  /// Kanji with about as many strokes as the answer.
+  Strokes,
+
+  /// Kanji about as common as the answer in the sources.
+  Frequency,
+
+  /// Kanji sharing an on-reading with the answer.
+  Reading,
+
+  /// Kanji that were mistaken for the answer, or the other way around, in past quizzes.
+  Confusion,
+
+  /// All of the above, where sharing components, readings or past mistakes counts the most.
+  #[default]
+  Mixed,
+}
 
 #[derive(Clone, Copy, Debug, Default, AsRefStr, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
@@ -23,6 +61,105 @@ pub enum Editor {
   Zed,
 }
 
+/// How kanji are bucketed into levels according to how often they are seen.
+/// Fields missing from the saved settings keep their default value.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Levels {
+  pub mode: LevelMode,
+
+  /// Each kanji gets the first level whose minimum it reaches, trying the highest ones first.
+  pub thresholds: Vec<LevelThreshold>,
+
+  /// Level of the kanji reaching none of the thresholds.
+  pub fallback: String,
+}
+
+impl Default for Levels {
+  fn default() -> Self {
+    let threshold = |name: &str, min: f64| LevelThreshold { name: name.to_owned(), min };
+    Self {
+      mode: LevelMode::Ratio,
+      thresholds: vec![
+        threshold("common", 0.001),
+        threshold("uncommon", 0.0001),
+        threshold("rare", 0.000_01),
+        threshold("very-rare", 0.000_001),
+      ],
+      fallback: "unknown".to_owned(),
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LevelMode {
+  /// Thresholds are ratios of all the kanji seen, from 0 to 1.
+  #[default]
+  Ratio,
+
+  /// Thresholds are percentiles of the frequency rank, from 0 to 100, so that 90 means
+  /// being seen more often than 90% of the other kanji.
+  Percentile,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelThreshold {
+  pub name: String,
+  pub min: f64,
+}
+
+/// Parameters of the review scheduler, a variant of SM-2 where every quiz answer is a review
+/// graded by whether it was correct, how long it took and how sure one was of it.
+/// Intervals are counted in days, and parameters missing from the saved settings keep
+/// their default value.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReviewParams {
+  /// Interval after the first passed review, and after a failed one.
+  pub first_interval: f64,
+  pub second_interval: f64,
+
+  /// Factor by which the interval grows after each passed review, starting with this value.
+  pub starting_ease: f64,
+  pub minimum_ease: f64,
+
+  /// Subtracted from the ease each time a review is failed.
+  pub ease_penalty: f64,
+
+  /// Subtracted from the ease when a review is passed slowly or without confidence,
+  /// and added to it when it is passed with a high confidence.
+  pub ease_step: f64,
+
+  /// Factor by which the interval grows after a hard review, instead of the ease.
+  pub hard_interval: f64,
+
+  /// Applied on top of the ease after an easy review.
+  pub easy_bonus: f64,
+
+  /// Applied to every interval after the second, so that lower values mean more reviews.
+  pub interval_modifier: f64,
+  pub maximum_interval: f64,
+}
+
+impl Default for ReviewParams {
+  fn default() -> Self {
+    Self {
+      first_interval: 1.0,
+      second_interval: 6.0,
+      starting_ease: 2.5,
+      minimum_ease: 1.3,
+      ease_penalty: 0.2,
+      ease_step: 0.15,
+      hard_interval: 1.2,
+      easy_bonus: 1.3,
+      interval_modifier: 1.0,
+      maximum_interval: 365.0,
+    }
+  }
+}
+
 fn get_or<T>(app: &AppHandle, key: &str, default: T) -> T
 where
   T: DeserializeOwned,
@@ -45,6 +182,10 @@ where
   app.pinia().get_or_else("settings", key, f)
 }
 
+pub fn distractor_strategy(app: &AppHandle) -> DistractorStrategy {
+  get_or(app, "distractorStrategy", DEFAULT_DISTRACTOR_STRATEGY)
+}
+
 pub fn editor(app: &AppHandle) -> Editor {
   get_or(app, "editor", DEFAULT_EDITOR)
 }
@@ -66,6 +207,24 @@ pub fn ignore_source_weight(app: &AppHandle) -> bool {
   get_or(app, "ignoreSourceWeight", DEFAULT_IGNORE_SOURCE_WEIGHT)
 }
 
+/// Whether `々` and `〆` count as kanji.
+pub fn iteration_marks(app: &AppHandle) -> bool {
+  get_or(app, "iterationMarks", DEFAULT_ITERATION_MARKS)
+}
+
+pub fn levels(app: &AppHandle) -> Levels {
+  get_or_default(app, "levels")
+}
+
+/// Whether variant forms of a kanji, such as `國` and `国`, are counted and searched as one.
+pub fn normalize_variants(app: &AppHandle) -> bool {
+  get_or(app, "normalizeVariants", DEFAULT_NORMALIZE_VARIANTS)
+}
+
+pub fn review_params(app: &AppHandle) -> ReviewParams {
+  get_or_default(app, "reviewParams")
+}
+
 pub fn set_chunk_size(app: &AppHandle) -> usize {
   get_or(app, "setChunkSize", DEFAULT_SET_CHUNK_SIZE)
 }
@@ -78,10 +237,39 @@ pub fn shuffle_snippets(app: &AppHandle) -> bool {
   get_or(app, "shuffleSnippets", DEFAULT_SHUFFLE_SNIPPETS)
 }
 
+/// How many times longer than the average correct answer a correct answer must take
+/// for it to count as slow.
+pub fn slow_answer_ratio(app: &AppHandle) -> f64 {
+  get_or(app, "slowAnswerRatio", DEFAULT_SLOW_ANSWER_RATIO)
+}
+
+/// How many sentences before and after the snippet are shown as its context.
+pub fn snippet_context(app: &AppHandle) -> usize {
+  get_or(app, "snippetContext", DEFAULT_SNIPPET_CONTEXT)
+}
+
 pub fn snippet_limit(app: &AppHandle) -> usize {
   get_or(app, "snippetLimit", DEFAULT_SNIPPET_LIMIT)
 }
 
+/// Maximum number of characters of a snippet and of each side of its context, where zero
+/// means there is no limit.
+pub fn snippet_max_len(app: &AppHandle) -> usize {
+  get_or(app, "snippetMaxLen", DEFAULT_SNIPPET_MAX_LEN)
+}
+
 pub fn snippet_min_len(app: &AppHandle) -> usize {
   get_or(app, "snippetMinLen", DEFAULT_SNIPPET_MIN_LEN)
 }
+
+/// Folder of a `MeCab` dictionary, such as IPADIC, used to split snippets into words instead of
+/// the small one bundled with the app, which only knows the most common words.
+pub fn tokenizer_dictionary(app: &AppHandle) -> Option<PathBuf> {
+  get_or_default(app, "tokenizerDictionary")
+}
+
+/// Whether words ending with okurigana, such as `食べる`, are counted along with kanji compounds.
+/// Words found by the tokenizer are always counted, since their okurigana are not a guess.
+pub fn word_okurigana(app: &AppHandle) -> bool {
+  get_or(app, "wordOkurigana", DEFAULT_WORD_OKURIGANA)
+}
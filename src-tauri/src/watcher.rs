@@ -0,0 +1,207 @@
+use crate::core::scan::blocking_scan_source;
+use crate::core::source::walk_source;
+use crate::core::task::Task;
+use crate::database::model::source::Source;
+use crate::database::sql_types::{KanjiChar, SourceId};
+use crate::manager::ManagerExt;
+use anyhow::Result;
+use itertools::Itertools;
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{DebounceEventResult, DebouncedEvent, Debouncer, new_debouncer};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::nonpoison::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast::{self, Receiver, Sender};
+
+pub const SOURCE_CHANGED_EVENT: &str = "source-changed";
+
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Watches the folders of every enabled source, keeping the index up to date.
+pub struct SourceWatcher {
+  debouncer: Mutex<Debouncer<RecommendedWatcher>>,
+  watched: Mutex<HashMap<SourceId, (PathBuf, RecursiveMode)>>,
+  sender: Sender<SourceChanged>,
+}
+
+impl SourceWatcher {
+  pub fn new(app: &AppHandle) -> Result<Self> {
+    let (sender, _) = broadcast::channel(32);
+    let debouncer = new_debouncer(DEBOUNCE_TIMEOUT, on_event(app, sender.clone()))?;
+    let watcher = Self {
+      debouncer: Mutex::new(debouncer),
+      watched: Mutex::new(HashMap::new()),
+      sender,
+    };
+
+    watcher.refresh(app)?;
+
+    Ok(watcher)
+  }
+
+  /// Starts watching newly enabled sources and stops watching the ones that were
+  /// disabled or removed since the last call.
+  pub fn refresh(&self, app: &AppHandle) -> Result<()> {
+    let sources = app.database().get_enabled_sources()?;
+    let mut debouncer = self.debouncer.lock();
+    let mut watched = self.watched.lock();
+
+    watched.retain(|id, (path, mode)| {
+      let keep = sources.iter().any(|source| {
+        source.id == *id && *source.path == **path && recursive_mode(source) == *mode
+      });
+
+      if !keep && let Err(err) = debouncer.watcher().unwatch(path) {
+        log::warn!("failed to unwatch {}: {err}", path.display());
+      }
+
+      keep
+    });
+
+    for source in sources {
+      if watched.contains_key(&source.id) {
+        continue;
+      }
+
+      let mode = recursive_mode(&source);
+      match debouncer.watcher().watch(&source.path, mode) {
+        Ok(()) => {
+          watched.insert(source.id, (source.path.to_path_buf(), mode));
+        }
+        Err(err) => log::warn!("failed to watch {}: {err}", source.path),
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn subscribe(&self) -> Receiver<SourceChanged> {
+    self.sender.subscribe()
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceChanged {
+  id: SourceId,
+
+  /// Kanji that were not found in the source before the change.
+  new_kanjis: Vec<KanjiChar>,
+}
+
+fn on_event(
+  app: &AppHandle,
+  sender: Sender<SourceChanged>,
+) -> impl FnMut(DebounceEventResult) + Send + use<> {
+  let app = app.clone();
+  move |result| {
+    match result {
+      Ok(events) => {
+        if let Err(err) = on_change(&app, &sender, &events) {
+          log::error!("failed to rescan changed sources: {err:?}");
+        }
+      }
+      Err(err) => log::error!("source watcher failed: {err}"),
+    }
+  }
+}
+
+fn on_change(
+  app: &AppHandle,
+  sender: &Sender<SourceChanged>,
+  events: &[DebouncedEvent],
+) -> Result<()> {
+  // A source failing to rescan should not keep the others from being rescanned.
+  for source in app.database().get_enabled_sources()? {
+    if let Err(err) = on_source_change(app, sender, &source, events) {
+      log::error!("failed to rescan {}: {err:?}", source.path);
+    }
+  }
+
+  Ok(())
+}
+
+fn on_source_change(
+  app: &AppHandle,
+  sender: &Sender<SourceChanged>,
+  source: &Source,
+  events: &[DebouncedEvent],
+) -> Result<()> {
+  if !is_source_change(app, source, events)? {
+    return Ok(());
+  }
+
+  let before = source_kanjis(app, source)?;
+  if blocking_scan_source(app, source, &Task::new())?.is_empty() {
+    return Ok(());
+  }
+
+  let new_kanjis = source_kanjis(app, source)?
+    .into_iter()
+    .filter(|kanji| !before.contains(kanji))
+    .sorted_unstable()
+    .collect();
+
+  let changed = SourceChanged { id: source.id, new_kanjis };
+  let _ = sender.send(changed.clone());
+  app.emit(SOURCE_CHANGED_EVENT, changed)?;
+
+  Ok(())
+}
+
+/// Whether any of the events is about a file that the scan walks, or one that is already
+/// indexed, so that changes to excluded or ignored files do not trigger a rescan.
+fn is_source_change(app: &AppHandle, source: &Source, events: &[DebouncedEvent]) -> Result<bool> {
+  let paths = events
+    .iter()
+    .map(|event| event.path.as_path())
+    .filter(|path| path.starts_with(&*source.path))
+    .collect_vec();
+
+  if paths.is_empty() {
+    return Ok(false);
+  }
+
+  let indexed = app
+    .database()
+    .get_source_files(source.id)?
+    .into_iter()
+    .map(|file| file.path.to_path_buf())
+    .collect::<HashSet<_>>();
+
+  if paths
+    .iter()
+    .any(|path| indexed.contains(*path))
+  {
+    return Ok(true);
+  }
+
+  let walked = walk_source(source)?;
+  Ok(
+    paths
+      .iter()
+      .any(|path| walked.iter().any(|it| it == path)),
+  )
+}
+
+fn recursive_mode(source: &Source) -> RecursiveMode {
+  if source.max_depth == Some(1) {
+    RecursiveMode::NonRecursive
+  } else {
+    RecursiveMode::Recursive
+  }
+}
+
+fn source_kanjis(app: &AppHandle, source: &Source) -> Result<HashSet<KanjiChar>> {
+  let kanjis = app
+    .database()
+    .get_kanji_counts(&[source.id])?
+    .into_iter()
+    .map(|(kanji, ..)| kanji)
+    .collect();
+
+  Ok(kanjis)
+}
@@ -0,0 +1,13 @@
+/** Line where the snippet starts. EPUB chapters have none, so their file opens at the top. */
+export function getSnippetLine(source: SnippetSource) {
+  return source.kind === 'chapter' ? 1 : source.line;
+}
+
+export function formatSnippetLocation(source: SnippetSource) {
+  if (source.kind === 'chapter') {
+    return `${source.path}#${source.chapter}:${source.position}`;
+  }
+  else {
+    return `${source.path}:${source.line}`;
+  }
+}
@@ -7,9 +7,14 @@ interface Snippet {
 
 type SnippetId = string;
 
-interface SnippetSource {
+type SnippetSource = {
   readonly id: SourceId;
   readonly name: string;
   readonly path: string;
-  readonly line: number;
-}
+  readonly weight: SourceWeight;
+} & SnippetLocation;
+
+type SnippetLocation =
+  | { readonly kind: 'line'; readonly line: number }
+  | { readonly kind: 'chapter'; readonly chapter: number; readonly position: number }
+  | { readonly kind: 'cue'; readonly line: number; readonly start: number; readonly end: number };